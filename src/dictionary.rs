@@ -1,35 +1,506 @@
-use std::sync::LazyLock;
-use crate::word::Word;
+use std::{io, num::NonZeroUsize, path::{Path, PathBuf}, sync::{LazyLock, Mutex, OnceLock}};
+use crate::{play::FeedbackMatrix, word::{Letter, Word}};
 
-pub fn sort_by_frequency(words: &mut [Word]) {
-  let mut freq_analysis = [[0; 26]; 5];
-  for word in &*words {
+/// How many of `words` have each letter at each position, indexed by position then [`Letter::index`]
+///
+/// [`Letter::index`]: crate::word::Letter::index
+pub fn positional_frequencies<const N: usize>(words: &[Word<N>]) -> [[u32; 26]; N] {
+  let mut freq_analysis = [[0; 26]; N];
+  for word in words {
     for (ch, freq) in word.into_iter().zip(freq_analysis.iter_mut()) {
       freq[ch.index()] += 1;
     }
   }
+  freq_analysis
+}
+
+/// Which of `words` have every one of `letters` somewhere, as many times as it's listed
+///
+/// With `N` letters, these are the anagrams of them.
+pub fn words_containing<const N: usize>(words: &[Word<N>], letters: &[Letter]) -> Vec<Word<N>> {
+  let mut wanted = [0u8; 26];
+  for ch in letters {
+    wanted[ch.index()] += 1;
+  }
+  words.iter()
+    .copied()
+    .filter(|word| word.letter_counts().iter().zip(&wanted).all(|(have, want)| have >= want))
+    .collect()
+}
 
-  words.sort_by_cached_key(|word|
+pub fn sort_by_frequency<const N: usize>(words: &mut [Word<N>]) {
+  let freq_analysis = positional_frequencies(words);
+
+  // unique words first, in one pass rather than a second sort to partition them
+  words.sort_by_cached_key(|word| (
+    !word.is_unique(),
     u32::MAX - word.iter()
       .copied()
       .enumerate()
       .map(|(i, ch)| freq_analysis[i][ch.index()])
-      .sum::<u32>()
-  );
-
-  // partition unique words to the front
-  words.sort_by_cached_key(|word| !word.is_unique());
-}
-
-pub static FIVE_LETTER_WORDS: LazyLock<Vec<Word>> = LazyLock::new(|| {
-  let mut words = include_bytes!("list.txt")
-    .split(|&ch| ch == b';')
-    .map(|word| {
-      debug_assert_eq!(word.len(), 5);
-      let bytes = unsafe { *(word.as_ptr() as *const [u8; 5]) };
-      Word::from_bytes(bytes).expect("words in list.txt should be valid")
-    })
-    .collect::<Vec<Word>>();
+      .sum::<u32>(),
+  ));
+}
+
+/// Like [`sort_by_frequency`], but also rewards each distinct letter for how common it is in any position
+///
+/// This favors covering many common letters over putting them in their most likely spots.
+pub fn sort_by_blended_frequency<const N: usize>(words: &mut [Word<N>]) {
+  let freq_analysis = positional_frequencies(words);
+  let mut overall = [0; 26];
+  for freq in &freq_analysis {
+    for (total, n) in overall.iter_mut().zip(freq) {
+      *total += n;
+    }
+  }
+
+  // unique words first, in one pass rather than a second sort to partition them
+  words.sort_by_cached_key(|word| {
+    let positional = word.iter()
+      .copied()
+      .enumerate()
+      .map(|(i, ch)| freq_analysis[i][ch.index()])
+      .sum::<u32>();
+    // averaged over the positions to weigh about the same as a positional count
+    let coverage = word.iter()
+      .copied()
+      .enumerate()
+      .filter(|&(i, ch)| !word[..i].contains(&ch))
+      .map(|(_, ch)| overall[ch.index()] / N as u32)
+      .sum::<u32>();
+    (!word.is_unique(), u32::MAX - (positional + coverage))
+  });
+}
+
+/// How candidates are ordered before the strategy picks from them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ranking {
+  /// [`sort_by_frequency`]
+  #[default]
+  Positional,
+
+  /// [`sort_by_blended_frequency`]
+  Blended,
+}
+
+impl Ranking {
+  pub fn sort<const N: usize>(self, words: &mut [Word<N>]) {
+    match self {
+      Self::Positional => sort_by_frequency(words),
+      Self::Blended => sort_by_blended_frequency(words),
+    }
+  }
+}
+
+impl std::str::FromStr for Ranking {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "positional" => Ok(Self::Positional),
+      "blended" => Ok(Self::Blended),
+      _ => Err(format!("unknown ranking `{s}`, expected one of: positional, blended")),
+    }
+  }
+}
+
+/// Which embedded word list to play with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+  #[default]
+  English,
+
+  /// Stored with its accents folded away, see [`fold_accents`]
+  Spanish,
+}
+
+impl Language {
+  /// Every word in the embedded list, in the order it's stored
+  pub fn words(self) -> Vec<Word> {
+    let list: &[u8] = match self {
+      Self::English => include_bytes!("list.txt"),
+      Self::Spanish => include_bytes!("list_es.txt"),
+    };
+    list.split(|&ch| ch == b';')
+      .map(|word| {
+        let bytes = <[u8; 5]>::try_from(word).expect("words in embedded lists should be five letters");
+        Word::from_bytes(bytes).expect("words in embedded lists should be valid")
+      })
+      .collect()
+  }
+
+  /// The first guess that tells the most about the embedded list, when it's both the guesses and the answers
+  ///
+  /// Found with `--find-opener`, which ranks every guess by the bits of information its feedback gives:
+  /// TARES gives 6.198 bits about the English list and CALOR 5.702 about the Spanish one.
+  /// It needs finding again if the list changes, and doesn't hold for custom lists.
+  pub const fn opener(self) -> Word {
+    use Letter::*;
+    match self {
+      Self::English => Word([T, A, R, E, S]),
+      Self::Spanish => Word([C, A, L, O, R]),
+    }
+  }
+}
+
+impl std::str::FromStr for Language {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "en" | "english" => Ok(Self::English),
+      "es" | "spanish" => Ok(Self::Spanish),
+      _ => Err(format!("unknown language `{s}`, expected one of: en, es")),
+    }
+  }
+}
+
+/// Strip the accent from a Latin-1 letter, e.g. `é` to `E`, leaving any other character as is
+///
+/// Only the 26 ASCII letters can be played, so word lists are folded with this before parsing.
+/// `Ñ` becomes `N` and `Ü` becomes `U`, so words that differ only by an accent count as the same word.
+/// Letters with no ASCII base, like `ß` or `Æ`, aren't folded and still make a word invalid.
+pub const fn fold_accents(ch: char) -> char {
+  match ch {
+    'À'..='Å' | 'à'..='å' => 'A',
+    'Ç' | 'ç' => 'C',
+    'È'..='Ë' | 'è'..='ë' => 'E',
+    'Ì'..='Ï' | 'ì'..='ï' => 'I',
+    'Ñ' | 'ñ' => 'N',
+    'Ò'..='Ö' | 'ò'..='ö' => 'O',
+    'Ù'..='Ü' | 'ù'..='ü' => 'U',
+    'Ý' | 'ý' | 'ÿ' => 'Y',
+    _ => ch,
+  }
+}
+
+/// Parse a word list with one or more words per line, separated by whitespace or `;`
+///
+/// Words may be in any case, and accents are folded away with [`fold_accents`].
+/// Any entry that still isn't `N` ASCII letters is reported with its line number.
+pub fn parse_list<const N: usize>(text: &str) -> io::Result<Vec<Word<N>>> {
+  let mut words = Vec::new();
+  for (line_num, line) in text.lines().enumerate() {
+    for entry in line.split(|ch: char| ch == ';' || ch.is_whitespace()).filter(|s| !s.is_empty()) {
+      let word = entry.chars().map(fold_accents).collect::<String>().parse::<Word<N>>()
+        .map_err(|e| io::Error::new(
+          io::ErrorKind::InvalidData,
+          format!("line {}: `{entry}` is not a {N}-letter word: {e}", line_num + 1),
+        ))?;
+      words.push(word);
+    }
+  }
+  if words.is_empty() {
+    return Err(io::Error::new(io::ErrorKind::InvalidData, "word list is empty"));
+  }
+  Ok(words)
+}
+
+/// Read a word list from a file (see [`parse_list`]) and sort it the same way as the embedded list
+///
+/// The sorted list is cached in [`cache_dir`], see [`load_cached`].
+pub fn load_from_path<const N: usize>(path: &Path) -> io::Result<Vec<Word<N>>> {
+  let text = std::fs::read_to_string(path)?;
+  load_cached(&text, cache_dir().as_deref())
+}
+
+/// Bumped whenever [`sort_by_frequency`] changes, so lists sorted the old way aren't reused
+const CACHE_VERSION: u32 = 1;
+
+/// Where [`load_from_path`] keeps sorted lists for the current user, if there's anywhere to keep them
+///
+/// `$XDG_CACHE_HOME/wordle-helper`, falling back on `~/.cache/wordle-helper`, or `%LOCALAPPDATA%\wordle-helper` on Windows.
+pub fn cache_dir() -> Option<PathBuf> {
+  if cfg!(feature = "wasm") {
+    return None;
+  }
+  let base = std::env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()).map(PathBuf::from)
+    .or_else(|| std::env::var_os("HOME").filter(|dir| !dir.is_empty()).map(|home| Path::new(&home).join(".cache")))
+    .or_else(|| std::env::var_os("LOCALAPPDATA").filter(|dir| !dir.is_empty()).map(PathBuf::from))?;
+  Some(base.join("wordle-helper"))
+}
+
+/// The file in `dir` a list with contents `text` is cached as, named by its hash and [`CACHE_VERSION`]
+pub fn cache_path(dir: &Path, text: &str) -> PathBuf {
+  dir.join(format!("sorted-v{CACHE_VERSION}-{}.txt", cache_key(text)))
+}
+
+fn cache_key(text: &str) -> String {
+  format!("{:016x}", hash_list(text))
+}
+
+/// FNV-1a, which unlike [`std::hash::DefaultHasher`] is the same from one build to the next
+fn hash_list(text: &str) -> u64 {
+  text.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ u64::from(b)).wrapping_mul(0x100000001b3))
+}
+
+/// Parse and sort the list `text`, reusing the copy in `cache_dir` if it was already sorted
+///
+/// The cache is the version and hash of the source list on the first line, followed by the sorted words.
+/// If it's missing or doesn't match, the list is sorted again and the cache rewritten.
+pub fn load_cached<const N: usize>(text: &str, cache_dir: Option<&Path>) -> io::Result<Vec<Word<N>>> {
+  let key = format!("v{CACHE_VERSION} {}", cache_key(text));
+  let cache = cache_dir.map(|dir| cache_path(dir, text));
+  if let Some(cache) = &cache
+    && let Ok(cached) = std::fs::read_to_string(cache)
+    && let Some((cached_key, list)) = cached.split_once('\n')
+    && cached_key == key
+    && let Ok(words) = parse_list(list)
+  {
+    return Ok(words);
+  }
+  let mut words = parse_list(text)?;
+  sort_by_frequency(&mut words);
+  if let Some(cache) = &cache {
+    let list = words.iter().map(Word::as_str).collect::<Vec<_>>().join(";");
+    // only a shortcut for next time, so the list is still usable if it can't be written
+    _ = write_cache(cache, &format!("{key}\n{list}"));
+  }
+  Ok(words)
+}
+
+/// Write to a fresh file and rename it over `cache`, which replaces a symlink instead of writing through it
+fn write_cache(cache: &Path, contents: &str) -> io::Result<()> {
+  if let Some(dir) = cache.parent() {
+    std::fs::create_dir_all(dir)?;
+  }
+  let partial = cache.with_extension(format!("{}.partial", std::process::id()));
+  std::fs::write(&partial, contents)?;
+  std::fs::rename(&partial, cache).inspect_err(|_| _ = std::fs::remove_file(&partial))
+}
+
+/// Parse a word frequency list, with a word and how often it's used on each line, separated by whitespace
+///
+/// Words that aren't five letters are skipped, so a list covering every length can be used as is.
+/// Sorted alphabetically, keeping the highest count of any repeated word.
+pub fn parse_prior(text: &str) -> io::Result<Vec<(Word, u64)>> {
+  let mut prior = Vec::new();
+  for (line_num, line) in text.lines().enumerate() {
+    let mut fields = line.split_whitespace();
+    let (Some(word), Some(count)) = (fields.next(), fields.next()) else {
+      if line.trim().is_empty() {
+        continue;
+      }
+      return Err(io::Error::new(io::ErrorKind::InvalidData, format!("line {}: expected a word and a count", line_num + 1)));
+    };
+    let count = count.parse::<u64>()
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: `{count}` is not a count: {e}", line_num + 1)))?;
+    if let Ok(word) = word.chars().map(fold_accents).collect::<String>().parse::<Word>() {
+      prior.push((word, count));
+    }
+  }
+  prior.sort_unstable_by(|(a, m), (b, n)| a.cmp(b).then(n.cmp(m)));
+  prior.dedup_by_key(|(word, _)| *word);
+  Ok(prior)
+}
+
+/// How often each word is used, if a list was provided; see [`parse_prior`]
+static PRIOR: OnceLock<Vec<(Word, u64)>> = OnceLock::new();
+
+/// Prefer common words among the last few candidates, using counts from [`parse_prior`]
+///
+/// Has no effect if a prior was already set.
+pub fn use_prior(prior: Vec<(Word, u64)>) {
+  _ = PRIOR.set(prior);
+}
+
+pub fn prior() -> Option<&'static [(Word, u64)]> {
+  PRIOR.get().map(Vec::as_slice)
+}
+
+/// Which embedded list [`ALLOWED_GUESSES`] is filled from
+static LANGUAGE: Mutex<Language> = Mutex::new(Language::English);
+
+/// Replaces the embedded list, if set before [`ALLOWED_GUESSES`] is first used
+static CUSTOM_GUESSES: Mutex<Option<Vec<Word>>> = Mutex::new(None);
+
+/// Replaces [`ALLOWED_GUESSES`] as the answer list, if set before [`ANSWERS`] is first used
+static CUSTOM_ANSWERS: Mutex<Option<Vec<Word>>> = Mutex::new(None);
+
+/// Play with the embedded list for `language` instead of English
+///
+/// Has no effect once [`ALLOWED_GUESSES`] has been initialized, or if custom guesses were provided.
+pub fn use_language(language: Language) {
+  *LANGUAGE.lock().unwrap() = language;
+}
+
+/// Use `words` instead of the embedded list
+///
+/// Has no effect once [`ALLOWED_GUESSES`] has been initialized.
+pub fn use_custom_guesses(words: Vec<Word>) {
+  *CUSTOM_GUESSES.lock().unwrap() = Some(words);
+}
+
+/// Only consider `words` as possible answers, while still guessing from [`ALLOWED_GUESSES`]
+///
+/// Has no effect once [`ANSWERS`] has been initialized.
+pub fn use_custom_answers(words: Vec<Word>) {
+  *CUSTOM_ANSWERS.lock().unwrap() = Some(words);
+}
+
+/// Every word that may be played as a guess
+///
+/// The embedded lists (see [`Language`]) only have five-letter words, the only length that ships with the crate.
+/// Lists of other lengths have to be loaded with [`load_from_path`].
+pub static ALLOWED_GUESSES: LazyLock<Vec<Word>> = LazyLock::new(|| {
+  if let Some(words) = CUSTOM_GUESSES.lock().unwrap().take() {
+    return words;
+  }
+  let mut words = LANGUAGE.lock().unwrap().words();
   sort_by_frequency(&mut words);
   words
 });
+
+/// Every word that may be the solution
+///
+/// Same as [`ALLOWED_GUESSES`] unless a separate answer list was provided.
+pub static ANSWERS: LazyLock<Vec<Word>> = LazyLock::new(|| {
+  CUSTOM_ANSWERS.lock().unwrap().take()
+    .unwrap_or_else(|| ALLOWED_GUESSES.clone())
+});
+
+/// Every allowed guess graded against every answer, built the first time it's needed
+///
+/// Takes a byte per pair, so this is only worth it when playing many games.
+pub fn feedback_matrix() -> &'static FeedbackMatrix {
+  static MATRIX: LazyLock<FeedbackMatrix> = LazyLock::new(|| FeedbackMatrix::new(
+    ALLOWED_GUESSES.as_slice(),
+    ANSWERS.as_slice(),
+    std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+  ));
+  &MATRIX
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_list() {
+    let words = parse_list::<5>("crane;Slate\n\n  AUDIO  trace\n").unwrap();
+    assert_eq!(words.iter().map(|w| w.as_str()).collect::<Vec<_>>(), ["CRANE", "SLATE", "AUDIO", "TRACE"]);
+    let words = parse_list::<6>("planet plants\n").unwrap();
+    assert_eq!(words.iter().map(|w| w.as_str()).collect::<Vec<_>>(), ["PLANET", "PLANTS"]);
+    assert!(parse_list::<6>("planet crane\n").unwrap_err().to_string().contains("is not a 6-letter word"));
+  }
+
+  #[test]
+  fn test_fold_accents() {
+    let words = parse_list::<5>("árbol Cañón\nCAFÉS").unwrap();
+    assert_eq!(words.iter().map(|w| w.as_str()).collect::<Vec<_>>(), ["ARBOL", "CANON", "CAFES"]);
+    assert_eq!(fold_accents('ß'), 'ß');
+    assert!(parse_list::<5>("straße").is_err());
+  }
+
+  #[test]
+  fn test_languages() {
+    let spanish = Language::Spanish.words();
+    assert!(spanish.contains(&"QUESO".parse().unwrap()));
+    assert!(spanish.is_sorted(), "list_es.txt should be sorted");
+    assert!(spanish.contains(&Language::Spanish.opener()));
+    assert!(Language::English.words().contains(&Language::English.opener()));
+    assert_eq!("es".parse::<Language>(), Ok(Language::Spanish));
+    assert!("klingon".parse::<Language>().is_err());
+  }
+
+  #[test]
+  fn test_positional_frequencies() {
+    let freq = positional_frequencies(&parse_list::<5>("CRANE SLATE TRACE").unwrap());
+    assert_eq!(freq[0][b'C' as usize - b'A' as usize], 1);
+    assert_eq!(freq[2][b'A' as usize - b'A' as usize], 3);
+    assert_eq!(freq[4][b'E' as usize - b'A' as usize], 3);
+    assert_eq!(freq.iter().map(|f| f.iter().sum::<u32>()).collect::<Vec<_>>(), [3; 5]);
+  }
+
+  #[test]
+  fn test_words_containing() {
+    let words = parse_list::<5>("CRANE NACRE CANER SLATE TRACE RACER EERIE").unwrap();
+    let letters = |s: &str| s.bytes().map(|b| Letter::from_u8(b).unwrap()).collect::<Vec<_>>();
+    let found = |s: &str| words_containing(&words, &letters(s)).iter().map(Word::to_string).collect::<Vec<_>>();
+    assert_eq!(found("ACERN"), ["CRANE", "NACRE", "CANER"]);
+    assert_eq!(found("RCA"), ["CRANE", "NACRE", "CANER", "TRACE", "RACER"]);
+    // RACER is the only word with two Rs
+    assert_eq!(found("RR"), ["RACER"]);
+    assert_eq!(found("EEE"), ["EERIE"]);
+    assert_eq!(found("").len(), words.len());
+    assert!(found("Q").is_empty());
+  }
+
+  #[test]
+  fn test_blended_frequency() {
+    let mut words = parse_list::<5>("CRANE SLATE SLATE TREES TREES TREES").unwrap();
+    let [crane, slate, trees] = ["CRANE", "SLATE", "TREES"].map(|s| s.parse::<Word>().unwrap());
+    // tied at 12 positionally, so CRANE stays first
+    sort_by_frequency(&mut words);
+    assert_eq!(words, [crane, slate, slate, trees, trees, trees]);
+    // but S and T appear 5 times overall, adding 5/5 each to SLATE
+    sort_by_blended_frequency(&mut words);
+    assert_eq!(words, [slate, slate, crane, trees, trees, trees]);
+    assert_eq!("blended".parse::<Ranking>(), Ok(Ranking::Blended));
+    assert!("alphabetical".parse::<Ranking>().is_err());
+  }
+
+  #[test]
+  fn test_parse_prior() {
+    let prior = parse_prior("the\t2000\nslate 40\n\ncrane\t300\nSLATE\t50\n").unwrap();
+    let word = |s: &str| s.parse::<Word>().unwrap();
+    assert_eq!(prior, [(word("CRANE"), 300), (word("SLATE"), 50)]);
+    assert!(parse_prior("crane\n").unwrap_err().to_string().starts_with("line 1:"));
+    assert!(parse_prior("crane 3\nslate many\n").unwrap_err().to_string().starts_with("line 2:"));
+  }
+
+  #[test]
+  fn test_parse_list_errors() {
+    let err = parse_list::<5>("CRANE\nSLATE\nTOOLONG\n").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().starts_with("line 3:"), "{err}");
+    let err = parse_list::<5>("CRANE\nCAFΣS\n").unwrap_err();
+    assert!(err.to_string().starts_with("line 2:"), "{err}");
+    assert!(parse_list::<5>("\n\n").is_err());
+  }
+
+  #[test]
+  fn test_load_from_path() {
+    let path = std::env::temp_dir().join(format!("wordle-helper-dict-{}.txt", std::process::id()));
+    std::fs::write(&path, "zzzzz\nslate\nsleet\n").unwrap();
+    let words = load_from_path::<5>(&path);
+    std::fs::remove_file(&path).unwrap();
+    if let Some(dir) = cache_dir() {
+      _ = std::fs::remove_file(cache_path(&dir, "zzzzz\nslate\nsleet\n"));
+    }
+    let words = words.unwrap();
+    assert_eq!(words.len(), 3);
+    assert!(words.contains(&Word::from_bytes(*b"SLATE").unwrap()));
+    assert!(load_from_path::<5>(&path).is_err());
+  }
+
+  #[test]
+  fn test_load_cached() {
+    let dir = std::env::temp_dir().join(format!("wordle-helper-cache-{}", std::process::id()));
+    let text = "zzzzz\nslate\nsleet\n";
+    let cache = cache_path(&dir, text);
+    let sorted = load_cached::<5>(text, Some(&dir)).unwrap();
+
+    // reversing the cached words without touching the key shows whether it was reused
+    let cached = std::fs::read_to_string(&cache).unwrap();
+    let (key, list) = cached.split_once('\n').unwrap();
+    let reversed = list.split(';').rev().collect::<Vec<_>>().join(";");
+    std::fs::write(&cache, format!("{key}\n{reversed}")).unwrap();
+    let reused = load_cached::<5>(text, Some(&dir)).unwrap();
+
+    // a different list is kept separately rather than evicting the first
+    let changed = load_cached::<5>("zzzzz\nslate\nsleet\ncrane\n", Some(&dir)).unwrap();
+    let files = std::fs::read_dir(&dir).unwrap().count();
+    // a cache from an older version of the sort isn't trusted
+    std::fs::write(&cache, format!("v0{}\n{reversed}", &key[key.find(' ').unwrap()..])).unwrap();
+    let stale = load_cached::<5>(text, Some(&dir)).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(reused, sorted.iter().rev().copied().collect::<Vec<_>>());
+    assert_eq!(changed.len(), 4);
+    assert_eq!(files, 2);
+    assert!(key.starts_with(&format!("v{CACHE_VERSION} ")));
+    assert_eq!(stale, sorted);
+    assert_eq!(load_cached::<5>(text, None).unwrap(), sorted);
+  }
+}
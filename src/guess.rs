@@ -1,8 +1,8 @@
-use std::cell::RefCell;
-use arrayvec::ArrayVec;
+use std::{num::NonZeroUsize, ops::RangeInclusive};
 use bitflags::bitflags;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use crate::{dictionary::*, play::grade_many, verbose_println, word::{Letter, Word}, OPTIONS};
+use crate::{dictionary::*, play::{grade_many, grade_many_iter, grade_many_with_threads_unchecked, FeedbackMatrix}, verbose_println, word::{Letter, Word}};
 
 bitflags!{
   #[derive(Debug, Clone, Copy)]
@@ -12,10 +12,15 @@ bitflags!{
     const P3 = 1 << 2;
     const P4 = 1 << 3;
     const P5 = 1 << 4;
+    const P6 = 1 << 5;
+    const P7 = 1 << 6;
   }
 }
 
 impl Positions {
+  /// Every position in a five-letter word
+  pub const ALL: Self = Self::first(5);
+
   pub const fn from_index(index: usize) -> Option<Self> {
     Self::from_bits(1u8 << index)
   }
@@ -24,6 +29,11 @@ impl Positions {
     debug_assert!(self.bits().count_ones() == 1);
     self.bits().trailing_zeros() as usize
   }
+
+  /// The first `n` positions, e.g. every position in an `n`-letter word
+  pub const fn first(n: usize) -> Self {
+    Self::from_bits_truncate((1u16 << n).wrapping_sub(1) as u8)
+  }
 }
 
 const _: () = {
@@ -32,8 +42,55 @@ const _: () = {
   assert!(Positions::P3.into_index() == 2);
   assert!(Positions::P4.into_index() == 3);
   assert!(Positions::P5.into_index() == 4);
+  assert!(Positions::P6.into_index() == 5);
+  assert!(Positions::P7.into_index() == 6);
+  assert!(Positions::first(5).bits() == 0b11111);
+  assert!(Positions::ALL.bits() == 0b11111);
 };
 
+/// One-based, e.g. `P1,P3,P5`, or `-` if empty
+impl std::fmt::Display for Positions {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.is_empty() {
+      return f.write_str("-");
+    }
+    for (i, position) in self.iter().enumerate() {
+      write!(f, "{}P{}", if i == 0 { "" } else { "," }, position.into_index() + 1)?;
+    }
+    Ok(())
+  }
+}
+
+/// Letters as one bit each, by [`Letter::index`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct LetterSet(u32);
+
+impl LetterSet {
+  const fn contains(self, ch: Letter) -> bool {
+    self.0 & (1 << ch.index()) != 0
+  }
+
+  /// Returns `false` if `ch` was already in the set
+  const fn insert(&mut self, ch: Letter) -> bool {
+    let is_new = !self.contains(ch);
+    self.0 |= 1 << ch.index();
+    is_new
+  }
+
+  const fn len(self) -> usize {
+    self.0.count_ones() as usize
+  }
+
+  const fn is_empty(self) -> bool {
+    self.0 == 0
+  }
+
+  /// In alphabetical order
+  fn iter(self) -> impl Iterator<Item = Letter> {
+    Letter::iter().filter(move |&ch| self.contains(ch))
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(u8)]
 pub enum LetterFeedback {
@@ -42,388 +99,2603 @@ pub enum LetterFeedback {
   Confirmed,
 }
 
+impl LetterFeedback {
+  /// The plain-text symbol [`WordFeedback`] parses this from
+  pub const fn symbol(self) -> char {
+    match self {
+      LetterFeedback::Excluded => '_',
+      LetterFeedback::Required => '?',
+      LetterFeedback::Confirmed => '+',
+    }
+  }
+}
+
+/// Shown as its glyph in [`FeedbackSymbols::DEFAULT`]
 impl std::fmt::Display for LetterFeedback {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    FeedbackSymbols::DEFAULT.glyph(*self).fmt(f)
+  }
+}
+
+/// How each [`LetterFeedback`] is typed in and shown, indexed by the feedback as `usize`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedbackSymbols {
+  /// Parsed as each feedback, before falling back on [`LetterFeedback::symbol`] and the color initials `b`, `y` and `g`
+  pub input: [char; 3],
+  /// Shown for each feedback
+  pub output: [char; 3],
+}
+
+impl FeedbackSymbols {
+  pub const DEFAULT: Self = Self {
+    input: ['_', '?', '+'],
+    output: Palette::Emoji.feedback(),
+  };
+
+  pub const fn glyph(&self, feedback: LetterFeedback) -> char {
+    self.output[feedback as usize]
+  }
+
+  /// Display `feedback` with these glyphs instead of the default ones
+  pub const fn display<const N: usize>(self, feedback: WordFeedback<N>) -> DisplayFeedback<N> {
+    DisplayFeedback(self, feedback)
+  }
+
+  /// Feedback typed with one symbol per letter
+  pub fn parse<const N: usize>(&self, s: &str) -> Result<WordFeedback<N>, FeedbackParseError> {
+    let found = s.chars().count();
+    if found != N {
+      return Err(FeedbackParseError::WrongLength { expected: N, found });
+    }
+    let mut values = [LetterFeedback::Excluded; N];
+    for (value, ch) in values.iter_mut().zip(s.chars()) {
+      *value = match self.input.iter().position(|&symbol| symbol == ch) {
+        Some(0) => LetterFeedback::Excluded,
+        Some(1) => LetterFeedback::Required,
+        Some(_) => LetterFeedback::Confirmed,
+        None => match ch {
+          '+' | 'g' | 'G' => LetterFeedback::Confirmed,
+          '?' | 'y' | 'Y' => LetterFeedback::Required,
+          '_' | 'b' | 'B' => LetterFeedback::Excluded,
+          _ => return Err(FeedbackParseError::UnknownSymbol(ch)),
+        },
+      };
+    }
+    Ok(WordFeedback(values))
+  }
+}
+
+impl Default for FeedbackSymbols {
+  fn default() -> Self {
+    Self::DEFAULT
+  }
+}
+
+/// See [`FeedbackSymbols::display`]
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayFeedback<const N: usize>(FeedbackSymbols, WordFeedback<N>);
+
+impl<const N: usize> std::fmt::Display for DisplayFeedback<N> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for ch in *self.1 {
+      self.0.glyph(ch).fmt(f)?;
+    }
+    Ok(())
+  }
+}
+
+/// Glyphs for feedback and the stats histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+  /// Colored squares, like the board
+  #[default]
+  Emoji,
+
+  /// Blue and orange instead of yellow and green, for colorblind players
+  Colorblind,
+
+  /// Plain characters, for terminals that can't show emoji
+  Ascii,
+}
+
+impl std::str::FromStr for Palette {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "default" | "emoji" => Ok(Self::Emoji),
+      "colorblind" => Ok(Self::Colorblind),
+      "ascii" => Ok(Self::Ascii),
+      _ => Err(format!("unknown palette `{s}`, expected one of: default, colorblind, ascii")),
+    }
+  }
+}
+
+impl Palette {
+  /// How many glyphs wide a histogram bar can be
+  pub const BAR_WIDTH: usize = 42;
+
+  /// Shown for each feedback, indexed like [`FeedbackSymbols::output`]
+  pub const fn feedback(self) -> [char; 3] {
+    match self {
+      Self::Emoji => ['\u{2B1C}', '🟨', '🟩'],
+      Self::Colorblind => ['\u{2B1C}', '🟦', '🟧'],
+      Self::Ascii => ['.', '+', '#'],
+    }
+  }
+
+  /// Bar for the games won on turn `turn` of `max_turns`, or lost if `turn == max_turns`
+  pub const fn turn_color(self, turn: u32, max_turns: u32) -> char {
+    const EMOJI: [char; 7] = ['🟪', '🟦', '🟩', '🟨', '🟧', '🟥', '\u{2B1C}'];
+    match self {
+      Self::Emoji if turn == max_turns => EMOJI[6],
+      Self::Emoji => EMOJI[(turn*6/max_turns) as usize],
+      Self::Colorblind if turn == max_turns => '\u{2B1C}',
+      Self::Colorblind if turn*2 < max_turns => '🟦',
+      Self::Colorblind => '🟧',
+      Self::Ascii if turn == max_turns => 'x',
+      Self::Ascii => '#',
+    }
+  }
+
+  /// The `i`th glyph of a probability bar, which gets cooler the longer it is
+  pub const fn bar_color(self, i: usize) -> char {
+    match self {
+      Self::Emoji => match i {
+        0..6 => '🟥',
+        6..13 => '🟧',
+        13..21 => '🟨',
+        21..29 => '🟩',
+        29..36 => '🟦',
+        _ => '🟪',
+      },
+      Self::Colorblind if i < Self::BAR_WIDTH/2 => '🟧',
+      Self::Colorblind => '🟦',
+      Self::Ascii => '#',
+    }
+  }
+
+  /// Pads a bar out to [`Self::BAR_WIDTH`]
+  pub const fn empty(self) -> char {
     match self {
-      LetterFeedback::Excluded => '\u{2B1C}',
-      LetterFeedback::Required => '🟨',
-      LetterFeedback::Confirmed => '🟩',
-    }.fmt(f)
+      Self::Emoji | Self::Colorblind => '⬛',
+      Self::Ascii => '.',
+    }
   }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(C, align(8))]
-pub struct WordFeedback([LetterFeedback; 5]);
+pub struct WordFeedback<const N: usize = 5>([LetterFeedback; N]);
 
-impl PartialOrd for WordFeedback {
+impl<const N: usize> PartialOrd for WordFeedback<N> {
   fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
     Some(self.cmp(other))
   }
 }
 
-impl Ord for WordFeedback {
+impl<const N: usize> Ord for WordFeedback<N> {
   fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-    self.to_u64().cmp(&other.to_u64())
+    // last letter is most significant, same as comparing `to_u64`
+    self.0.iter().rev().cmp(other.0.iter().rev())
   }
 }
 
-impl std::hash::Hash for WordFeedback {
+impl<const N: usize> std::hash::Hash for WordFeedback<N> {
   fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-    self.to_u64().hash(state);
+    self.0.hash(state);
   }
 }
 
-impl std::ops::Deref for WordFeedback {
-  type Target = [LetterFeedback; 5];
+impl<const N: usize> std::ops::Deref for WordFeedback<N> {
+  type Target = [LetterFeedback; N];
 
   fn deref(&self) -> &Self::Target {
     &self.0
   }
 }
 
-impl std::ops::DerefMut for WordFeedback {
+impl<const N: usize> std::ops::DerefMut for WordFeedback<N> {
   fn deref_mut(&mut self) -> &mut Self::Target {
     &mut self.0
   }
 }
 
-impl std::fmt::Display for WordFeedback {
+/// Shown with [`FeedbackSymbols::DEFAULT`], see [`FeedbackSymbols::display`] for others
+impl<const N: usize> std::fmt::Display for WordFeedback<N> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    for ch in self.0 {
-      ch.fmt(f)?;
-    }
-    Ok(())
+    FeedbackSymbols::DEFAULT.display(*self).fmt(f)
   }
 }
 
-impl WordFeedback {
-  pub const COMBINATIONS: usize = 3usize.pow(5);
+impl<const N: usize> WordFeedback<N> {
+  pub const COMBINATIONS: usize = 3usize.pow(N as u32);
 
   #[inline(always)]
-  pub const fn new(values: [LetterFeedback; 5]) -> Self {
+  pub const fn new(values: [LetterFeedback; N]) -> Self {
     Self(values)
   }
 
+  /// One byte per letter, first letter in the least significant byte
   #[inline(always)]
   pub const fn to_u64(self) -> u64 {
-    unsafe { std::mem::transmute::<_, u64>(self) }
+    const { assert!(N <= 8, "feedback does not fit in a u64") };
+    let mut value = 0;
+    let mut i = 0;
+    while i < N {
+      value |= (self.0[i] as u64) << (8*i);
+      i += 1;
+    }
+    value
+  }
+
+  /// Dense index in `0..COMBINATIONS`, reading each letter as a base-3 digit (first letter least significant)
+  pub const fn to_index(self) -> u16 {
+    const { assert!(N <= 10, "feedback index does not fit in a u16") };
+    let mut index = 0;
+    let mut i = N;
+    while i > 0 {
+      i -= 1;
+      index = index*3 + self.0[i] as u16;
+    }
+    index
+  }
+
+  /// Inverse of [`Self::to_index`]
+  pub const fn from_index(mut index: u16) -> Option<Self> {
+    if index as usize >= Self::COMBINATIONS {
+      return None;
+    }
+    let mut values = [LetterFeedback::Excluded; N];
+    let mut i = 0;
+    while i < N {
+      values[i] = match index % 3 {
+        0 => LetterFeedback::Excluded,
+        1 => LetterFeedback::Required,
+        _ => LetterFeedback::Confirmed,
+      };
+      index /= 3;
+      i += 1;
+    }
+    Some(Self(values))
   }
 }
 
-struct FeedbackMap<T> {
-  data: Vec<(WordFeedback, T)>,
+#[cfg(feature = "serde")]
+impl LetterFeedback {
+  const TAGS: [&str; 3] = ["grey", "yellow", "green"];
 }
 
-impl<T> FeedbackMap<T> {
-  pub fn with_capacity(capacity: usize) -> Self {
-    Self {
-      data: Vec::with_capacity(capacity),
+/// Feedback is tagged by its color on the board
+#[cfg(feature = "serde")]
+impl serde::Serialize for LetterFeedback {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(Self::TAGS[*self as usize])
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LetterFeedback {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    match String::deserialize(deserializer)?.as_str() {
+      "grey" => Ok(Self::Excluded),
+      "yellow" => Ok(Self::Required),
+      "green" => Ok(Self::Confirmed),
+      tag => Err(serde::de::Error::unknown_variant(tag, &Self::TAGS)),
     }
   }
+}
 
-  pub const fn len(&self) -> usize {
-    self.data.len()
+/// Word feedback is a sequence of letter feedback
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for WordFeedback<N> {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(self.0.iter())
   }
+}
 
-  pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, key: WordFeedback, f: F) -> &mut T {
-    match self.data.binary_search_by_key(&key, |(k, _)| *k) {
-      Ok(idx) => &mut self.data[idx].1,
-      Err(idx) => {
-        self.data.insert(idx, (key, f()));
-        &mut self.data[idx].1
-      }
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for WordFeedback<N> {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let values = Vec::<LetterFeedback>::deserialize(deserializer)?;
+    let len = values.len();
+    <[LetterFeedback; N]>::try_from(values)
+      .map(Self)
+      .map_err(|_| serde::de::Error::invalid_length(len, &format!("{N} letters").as_str()))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedbackParseError {
+  /// The feedback didn't have one symbol per letter
+  WrongLength { expected: usize, found: usize },
+  /// A symbol didn't correspond to any feedback
+  UnknownSymbol(char),
+}
+
+impl std::fmt::Display for FeedbackParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::WrongLength { expected, found } => write!(f, "expected {expected} feedback symbols, found {found}"),
+      Self::UnknownSymbol(ch) => write!(f, "unknown feedback symbol {ch:?}, expected one of `+?_` or `gyb`"),
     }
   }
+}
+
+impl std::error::Error for FeedbackParseError {}
+
+impl<const N: usize> std::str::FromStr for WordFeedback<N> {
+  type Err = FeedbackParseError;
+
+  /// One symbol per letter, see [`FeedbackSymbols::parse`] for others:
+  /// - `+`, `g` or `G`: [`LetterFeedback::Confirmed`]
+  /// - `?`, `y` or `Y`: [`LetterFeedback::Required`]
+  /// - `_`, `b` or `B`: [`LetterFeedback::Excluded`]
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    FeedbackSymbols::DEFAULT.parse(s)
+  }
+}
+
+pub(crate) struct FeedbackMap<T, const N: usize> {
+  data: Vec<(WordFeedback<N>, T)>,
+}
+
+impl<T, const N: usize> FeedbackMap<T, N> {
+  pub const fn len(&self) -> usize {
+    self.data.len()
+  }
 
-  pub fn values<'a>(&'a self) -> std::iter::Map<std::slice::Iter<'a, (WordFeedback, T)>, fn(&'a (WordFeedback, T)) -> &'a T> {
+  pub fn values(&self) -> impl Iterator<Item = &T> {
     self.data.iter().map(|x| &x.1)
   }
 
-  pub fn entries(&self) -> std::slice::Iter<'_, (WordFeedback, T)> {
+  pub fn entries(&self) -> std::slice::Iter<'_, (WordFeedback<N>, T)> {
     self.data.iter()
   }
-}
 
-pub struct Guesser {
-  candidates: Vec<Word>,
-  /// Sorted alphabetically
-  excluded: ArrayVec<Letter, {26 - 5}>,
-  /// Sorted alphabetically
-  required: ArrayVec<(Letter, Positions), 5>,
-  confirmed: [Option<Letter>; 5],
+  pub fn into_entries(self) -> Vec<(WordFeedback<N>, T)> {
+    self.data
+  }
 }
 
-thread_local! {
-  static BUFFER: RefCell<Vec<WordFeedback>> = RefCell::new(
-    Vec::with_capacity(FIVE_LETTER_WORDS.len()*FIVE_LETTER_WORDS.len())
-  );
-
-  static TIEBREAKERS: RefCell<Vec<(Word, FeedbackMap<Vec<Word>>)>> = RefCell::new(
-    Vec::with_capacity(FIVE_LETTER_WORDS.len()),
-  );
+/// Groups values by feedback in O(1) per insert, with a slot for each of the [`WordFeedback::COMBINATIONS`]
+///
+/// Reuse one for many guesses with [`Self::take`], so the slots are only allocated once.
+pub(crate) struct DenseFeedbackMap<T, const N: usize> {
+  /// Indexed by [`WordFeedback::to_index`], which sorts the same as the feedback itself
+  slots: Box<[Option<T>]>,
+  /// Indices of the filled slots, in insertion order
+  occupied: Vec<u16>,
 }
 
-impl Guesser {
-  pub fn new(mut candidates_buf: Vec<Word>) -> Self {
-    candidates_buf.clear();
-    candidates_buf.extend_from_slice(FIVE_LETTER_WORDS.as_slice());
+impl<T, const N: usize> DenseFeedbackMap<T, N> {
+  pub fn new() -> Self {
     Self {
-      candidates: candidates_buf,
-      excluded: ArrayVec::new(),
-      required: ArrayVec::new(),
-      confirmed: [const { None }; 5],
+      slots: std::iter::repeat_with(|| None).take(WordFeedback::<N>::COMBINATIONS).collect(),
+      occupied: Vec::with_capacity(32),
     }
   }
 
-  pub fn extract_resources(self) -> Vec<Word> {
-    self.candidates
+  pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, key: WordFeedback<N>, f: F) -> &mut T {
+    let index = key.to_index();
+    let slot = &mut self.slots[index as usize];
+    if slot.is_none() {
+      self.occupied.push(index);
+    }
+    slot.get_or_insert_with(f)
   }
 
-  pub fn guess(&self) -> Option<&Word> {
-    self.candidates.first()
+  /// Filled slots, in insertion order
+  pub fn values(&self) -> impl Iterator<Item = &T> {
+    self.occupied.iter().map(|&i| self.slots[i as usize].as_ref().unwrap())
   }
 
-  pub fn candidates(&self) -> &[Word] {
-    &self.candidates
+  /// Filled slots in feedback order
+  pub fn entries(&self) -> impl DoubleEndedIterator<Item = (WordFeedback<N>, &T)> {
+    self.slots.iter()
+      .enumerate()
+      .filter_map(|(i, slot)| slot.as_ref().map(|value| (WordFeedback::from_index(i as u16).unwrap(), value)))
   }
 
-  fn confirm(&mut self, idx: usize, ch: Letter) {
-    self.confirmed[idx] = Some(ch);
-    verbose_println!("letter '{ch}' is confirmed at position {}", idx + 1);
+  /// Move the filled slots out in feedback order, leaving every slot empty
+  pub fn take(&mut self) -> FeedbackMap<T, N> {
+    self.occupied.sort_unstable();
+    let data = self.occupied.drain(..)
+      .map(|i| (WordFeedback::from_index(i).unwrap(), self.slots[i as usize].take().unwrap()))
+      .collect();
+    FeedbackMap { data }
   }
+}
 
-  /// If only one possible space, treat as confirmed
-  ///
-  /// Returns `true` if an unknown was confirmed
-  fn pidgeon(&mut self, idx: usize) -> bool {
-    let (ch, p) = self.required[idx];
-    let confirmed_positions = Positions::from_iter(
-      self.confirmed.iter()
-        .enumerate()
-        .filter(|(_, c)| c.is_some_and(|c| c != ch))
-        .map(|(i, _)| Positions::from_index(i).unwrap())
-    );
-    let possible_positions = p
-      .union(confirmed_positions)
-      .complement();
-    let num_possible_positions = possible_positions.bits().count_ones();
-    assert_ne!(num_possible_positions, 0, "letter '{ch}' has no possible placement");
-    verbose_println!("letter '{ch}' can only be placed in {possible_positions:?}");
-    if num_possible_positions == 1 {
-      assert!(!possible_positions.is_empty());
-      let only_open = possible_positions.into_index();
-      verbose_println!("letter '{ch}' can only be placed at position {}", only_open + 1);
-      self.confirm(only_open, ch);
-      _ = self.required.remove(idx);
-      true
-    } else {
-      false
+/// Expected information, in bits, revealed by a guess that partitions `total` candidates into `mapping`
+fn entropy<T: AsRef<[Word<N>]>, const N: usize>(mapping: &FeedbackMap<T, N>, total: usize) -> f64 {
+  let total = total as f64;
+  mapping.values()
+    .map(|words| {
+      let p = words.as_ref().len() as f64 / total;
+      -p * p.log2()
+    })
+    .sum()
+}
+
+/// How the next guess is chosen from the remaining candidates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+  /// Prefer candidates made of common letters, with a tiebreaker when only a few remain
+  #[default]
+  Frequency,
+
+  /// Prefer the guess whose feedback has the highest Shannon entropy over the remaining candidates
+  Entropy,
+
+  /// Prefer the guess that leaves the fewest candidates in the worst case, then the most buckets
+  Minimax,
+}
+
+/// How a guess would split the remaining candidates
+struct Partition<const N: usize> {
+  guess: Word<N>,
+  bits: f64,
+  largest: usize,
+  buckets: usize,
+  is_candidate: bool,
+}
+
+impl std::str::FromStr for Strategy {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "frequency" => Ok(Self::Frequency),
+      "entropy" => Ok(Self::Entropy),
+      "minimax" => Ok(Self::Minimax),
+      _ => Err(format!("unknown strategy `{s}`, expected one of: frequency, entropy, minimax")),
     }
   }
+}
 
-  pub fn analyze(&mut self, chars: [(Letter, LetterFeedback); 5]) {
-    if !matches!(chars, [
-      (_, LetterFeedback::Confirmed),
-      (_, LetterFeedback::Confirmed),
-      (_, LetterFeedback::Confirmed),
-      (_, LetterFeedback::Confirmed),
-      (_, LetterFeedback::Confirmed),
-    ]) {
-      let word_used = Word(chars.map(|(c, _)| c));
-      if let Some(pos) = self.candidates.iter().position(|word| word == &word_used) {
-        _ = self.candidates.remove(pos);
-      } // else: user-provided word
+/// Excluded, required and confirmed letters and letter counts, in that order
+type Knowledge<const N: usize> = (LetterSet, [Positions; 26], [Option<Letter>; N], [(u8, u8); 26]);
+
+/// Told about the solver's decisions as it makes them, for UIs that want more than verbose printouts
+///
+/// Every event does nothing by default, as does the `()` observer a [`Guesser`] starts with.
+/// Letter events fire while feedback is analyzed, even if [`Guesser::analyze`] then rejects it as a contradiction.
+pub trait Observer<const N: usize = 5> {
+  /// `letter` is known to be at position `idx`
+  fn on_letter_confirmed(&mut self, _idx: usize, _letter: Letter) {}
+  /// `letter` is not in the word at all
+  fn on_letter_excluded(&mut self, _letter: Letter) {}
+  /// `word` will be suggested to narrow down the candidates, though it can't be the answer
+  fn on_tiebreaker_chosen(&mut self, _word: Word<N>) {}
+  /// A prune left `after` of the `before` candidates
+  fn on_candidates_pruned(&mut self, _before: usize, _after: usize) {}
+}
+
+impl<const N: usize> Observer<N> for () {}
+
+/// Solves for an `N`-letter word; only five-letter words have embedded lists
+pub struct Guesser<const N: usize = 5> {
+  candidates: Vec<Word<N>>,
+  /// At most `26 - N` letters
+  excluded: LetterSet,
+  /// Where each letter in the word is ruled out of, indexed by [`Letter::index`], empty if it isn't known to be in the word
+  required: [Positions; 26],
+  confirmed: [Option<Letter>; N],
+  /// The fewest and most copies of each letter the solution can have, indexed by [`Letter::index`]
+  counts: [(u8, u8); 26],
+  /// Words that may be the solution
+  answers: &'static [Word<N>],
+  /// Words ruled out by the user, sorted alphabetically
+  blocked: Vec<Word<N>>,
+  /// What was known before each analyzed feedback this game, most recent last, at most `max_turns` deep
+  history: Vec<Knowledge<N>>,
+  /// Always suggested first, in place of the best ranked word
+  opener: Option<Word<N>>,
+  /// Words that may be suggested, even if they can't be the solution
+  guesses: &'static [Word<N>],
+  /// Every suggestion must reuse all confirmed and required letters
+  is_hardmode: bool,
+  strategy: Strategy,
+  /// How candidates are ordered after each prune
+  ranking: Ranking,
+  /// Burners are only worth spending before the last turn
+  max_turns: u32,
+  /// How many candidates there must be for [`Strategy::Frequency`] to look for a burner
+  tiebreak_window: RangeInclusive<usize>,
+  /// Burners may be suggested at all, by any strategy
+  is_tiebreak: bool,
+  /// Print the "thought process" to stdout while playing
+  is_verbose: bool,
+  /// How many threads to grade guesses with
+  threads: NonZeroUsize,
+  /// Grades to look up instead of computing
+  matrix: Option<&'static FeedbackMatrix<N>>,
+  /// How often each word is used, sorted alphabetically
+  prior: Option<&'static [(Word<N>, u64)]>,
+  /// The first candidate is only there to narrow down the rest
+  has_burner: bool,
+  /// How many of the best suggestions were passed on with [`Guesser::skip`] this turn
+  skipped: usize,
+  /// Told about each decision
+  observer: Box<dyn Observer<N> + Send>,
+  /// Scratch space for grading guesses against the candidates, kept so later turns don't reallocate it
+  grades: Vec<WordFeedback<N>>,
+}
+
+/// Feedback that can't be true given what's already known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contradiction {
+  /// The letter was marked as not in the word, but also as in it
+  Excluded(Letter),
+  /// The letter must be in the word, but there's nowhere left to put it
+  Unplaceable(Letter),
+  /// Two different letters were confirmed at the same position
+  ConfirmedConflict { position: usize, letters: (Letter, Letter) },
+  /// The letter was confirmed at a position it was already ruled out of
+  RuledOut { letter: Letter, position: usize },
+  /// More letters were marked as not in the word than the alphabet can spare
+  TooManyExcluded,
+  /// The letter was shown more times than an earlier grey limited it to
+  CountConflict(Letter),
+}
+
+impl std::fmt::Display for Contradiction {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Excluded(ch) => write!(f, "letter '{ch}' was marked as both in and not in the word"),
+      Self::Unplaceable(ch) => write!(f, "letter '{ch}' is required but has no position left"),
+      Self::ConfirmedConflict { position, letters: (a, b) } => write!(f, "both '{a}' and '{b}' were confirmed at position {}", position + 1),
+      Self::RuledOut { letter, position } => write!(f, "letter '{letter}' was both confirmed and ruled out at position {}", position + 1),
+      Self::TooManyExcluded => write!(f, "too many letters were marked as not in the word"),
+      Self::CountConflict(ch) => write!(f, "letter '{ch}' was marked in the word more times than it can be"),
     }
+  }
+}
 
-    for (i, (ch, stat)) in chars.into_iter().enumerate() {
-      match stat {
-        LetterFeedback::Excluded => {
-          if let Err(pos) = self.excluded.binary_search(&ch) {
-            self.excluded.insert(pos, ch);
-            verbose_println!("letter '{ch}' is not in the word");
-          }
-        }
+impl std::error::Error for Contradiction {}
 
-        LetterFeedback::Required => {
-          let pos = Positions::from_index(i).unwrap();
-          let idx = match self.required.binary_search_by_key(&ch, |(r, _)| *r) {
-            Ok(idx) => { self.required[idx].1.insert(pos); idx },
-            Err(idx) => { self.required.insert(idx, (ch, pos)); idx },
-          };
-          verbose_println!("letter '{ch}' is required but cannot be in {:?}", self.required[idx].1);
-          _ = self.pidgeon(idx);
-        }
+/// The first thing that rules a word out as a candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+  /// A different letter was confirmed at this position
+  WrongConfirmed { position: usize, expected: Letter, found: Letter },
+  /// The word is missing a letter known to be in the solution
+  MissingRequired(Letter),
+  /// The word only has a required letter where it was already ruled out
+  ForbiddenPosition { letter: Letter, position: usize },
+  /// The word was ruled out with [`Guesser::exclude_word`]
+  Blocked,
+  /// The word contains a letter known not to be in the solution
+  Excluded(Letter),
+  /// The word has fewer or more copies of a letter than the solution can
+  WrongCount { letter: Letter, found: u8, min: u8, max: u8 },
+}
 
-        LetterFeedback::Confirmed => {
-          self.confirm(i, ch);
-          if let Ok(i) = self.required.binary_search_by_key(&ch, |(ch, _)| *ch) {
-            verbose_println!("letter '{ch}' no longer unknown");
-            _ = self.required.remove(i);
-          }
+impl std::fmt::Display for RejectReason {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::WrongConfirmed { position, expected, found } => write!(f, "position {} is '{expected}', not '{found}'", position + 1),
+      Self::MissingRequired(ch) => write!(f, "it doesn't contain '{ch}'"),
+      Self::ForbiddenPosition { letter, position } => write!(f, "'{letter}' was already ruled out at position {}", position + 1),
+      Self::Blocked => write!(f, "it was excluded by hand"),
+      Self::Excluded(ch) => write!(f, "it contains '{ch}'"),
+      Self::WrongCount { letter, found, min, max } => {
+        write!(f, "it has {found} '{letter}', but the word has ")?;
+        if min == max {
+          write!(f, "exactly {min}")
+        } else if found < min {
+          write!(f, "at least {min}")
+        } else {
+          write!(f, "at most {max}")
         }
       }
     }
+  }
+}
 
-    verbose_println!("draining...");
-    'outer: loop {
-      for i in 0..self.required.len() {
-        if self.pidgeon(i) {
-          continue 'outer;
+/// The current suggestion and what the solver expects of it, see [`Guesser::suggest`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion<const N: usize = 5> {
+  pub word: Word<N>,
+  /// Chosen only to narrow down the candidates, it can't be the answer
+  pub is_burner: bool,
+  /// Candidates the answer could still be, not counting a burner
+  pub candidates_remaining: usize,
+  /// Candidates expected to be left after playing `word`, see [`Guesser::expected_remaining`]
+  pub expected_remaining: f64,
+}
+
+/// What a [`Guesser::analyze`] made of the feedback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Analyzed {
+  /// Every letter was confirmed, so the word played was the answer
+  pub solved: bool,
+}
+
+/// How much a [`Guesser::prune`] narrowed the candidates down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pruned {
+  /// Candidates before the prune, after [`Guesser::analyze`] already dropped the word played
+  pub before: usize,
+  /// Candidates that still fit, not counting a tiebreaker added after
+  pub after: usize,
+}
+
+impl Pruned {
+  /// How many candidates the prune ruled out
+  pub const fn eliminated(self) -> usize {
+    self.before - self.after
+  }
+}
+
+/// Everything a [`Guesser`] has learned so far, without the candidates it implies
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GuesserState {
+  /// Letters not in the solution
+  pub excluded: Vec<Letter>,
+  /// Letters in the solution whose position isn't known yet
+  pub required: Vec<RequiredLetter>,
+  /// Letters known to be in each position
+  pub confirmed: [Option<Letter>; 5],
+  /// Bounds on how many copies of a letter there are, for letters where any are known
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub counts: Vec<LetterCount>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LetterCount {
+  pub letter: Letter,
+  pub min: u8,
+  pub max: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RequiredLetter {
+  pub letter: Letter,
+  /// Zero-based positions the letter is known not to be in
+  pub not_at: Vec<usize>,
+}
+
+impl Guesser {
+  pub fn new(candidates_buf: Vec<Word>) -> Self {
+    Self::with_buffer(candidates_buf, ANSWERS.as_slice(), ALLOWED_GUESSES.as_slice())
+  }
+
+  /// Look up grades in `matrix` rather than computing them each turn
+  ///
+  /// `matrix` must have been graded for this guesser's guesses.
+  /// Candidates it has no column for are still graded on the fly.
+  pub fn set_feedback_matrix(&mut self, matrix: &'static FeedbackMatrix) {
+    assert!(std::ptr::eq(matrix.guesses(), self.guesses), "feedback matrix is for a different guess list");
+    self.matrix = Some(matrix);
+  }
+
+  pub fn export_state(&self) -> GuesserState {
+    GuesserState {
+      excluded: self.excluded.iter().collect(),
+      required: self.required_letters()
+        .map(|(letter, pos)| RequiredLetter {
+          letter,
+          not_at: pos.iter().map(Positions::into_index).collect(),
+        })
+        .collect(),
+      confirmed: self.confirmed,
+      counts: Letter::iter().zip(self.counts)
+        .filter(|&(_, bounds)| bounds != Self::ANY_COUNT)
+        .map(|(letter, (min, max))| LetterCount { letter, min, max })
+        .collect(),
+    }
+  }
+
+  /// Replace everything learned so far with `state`
+  ///
+  /// Candidates are refilled from the answer list, call [`Self::prune`] to narrow them back down.
+  pub fn import_state(&mut self, state: &GuesserState) -> Result<(), String> {
+    let mut excluded = LetterSet::default();
+    for &ch in &state.excluded {
+      if !excluded.contains(ch) {
+        if excluded.len() >= 26 - 5 {
+          return Err("too many excluded letters".to_string());
         }
+        excluded.insert(ch);
       }
-      break;
     }
-    verbose_println!("feedback complete");
+    let mut required = [Positions::empty(); 26];
+    let mut counts = [Self::ANY_COUNT; 26];
+    for &LetterCount { letter, min, max } in &state.counts {
+      counts[letter.index()] = (min, max);
+    }
+    for RequiredLetter { letter, not_at } in &state.required {
+      for &i in not_at {
+        required[letter.index()] |= (i < 5).then(|| Positions::from_index(i)).flatten()
+          .ok_or_else(|| format!("position {i} is out of range"))?;
+      }
+      // not ruled out of anywhere yet, so only its count can say it's in the word
+      if not_at.is_empty() {
+        counts[letter.index()].0 = counts[letter.index()].0.max(1);
+      }
+    }
+    if required.iter().filter(|p| !p.is_empty()).count() > 5 {
+      return Err("too many required letters".to_string());
+    }
+    for (letter, (min, max)) in Letter::iter().zip(counts) {
+      if min > max || max > 5 {
+        return Err(format!("letter '{letter}' can't have between {min} and {max} copies"));
+      }
+    }
+    self.reset();
+    self.excluded = excluded;
+    self.required = required;
+    self.confirmed = state.confirmed;
+    self.counts = counts;
+    if let Err(e) = self.check_consistency() {
+      self.reset();
+      return Err(e.to_string());
+    }
+    Ok(())
   }
 
-  #[inline(never)]
-  fn encode_burner(&self) -> Option<Word> {
-    TIEBREAKERS.with_borrow_mut(|possible_tiebreakers| {
-      possible_tiebreakers.clear();
-
-      BUFFER.with_borrow_mut(|buf| {
-        // Pretend the candidate IS the actual word.
-        // If that were the case, how would our tiebreaker be judged?
-        buf.clear();
-        buf.par_extend(grade_many(FIVE_LETTER_WORDS.as_slice(), self.candidates.as_slice()).map(|(_, _, x)| x));
-
-        for (i, guess) in FIVE_LETTER_WORDS.iter().copied().enumerate() {
-          let mut mapping = FeedbackMap::with_capacity(8);
-          for (j, word) in self.candidates.iter().copied().enumerate() {
-            let encoding = buf[i * self.candidates.len() + j];
-            mapping.get_or_insert_with(encoding, || Vec::with_capacity(8))
-              .push(word);
-          }
-          possible_tiebreakers.push((guess, mapping));
-        }
-      });
-
-      // don't bother if the burner would have been just as effective as trying both
-      possible_tiebreakers.retain(|(_, mapping)| mapping.len() > 2);
-
-      // prefer words with fewer letters we already know
-      possible_tiebreakers.sort_by_cached_key(|(w, _)|
-        self.excluded.iter().copied()
-          .chain(self.required.iter().copied().map(|(ch, _)| ch))
-          .chain(self.confirmed.iter().copied().filter_map(|ch| ch))
-          .filter(|ch| w.contains(ch))
-          .count()
-      );
-
-      // prefer words with more tiebreakers
-      possible_tiebreakers.sort_by_key(|(_, m)| usize::MAX - m.len());
-
-      // prefer more potent tiebreakers
-      possible_tiebreakers.sort_by_key(|(_, m)|
-        m.values()
-          // more words in the same bucket are exponentially less valuable than having the same number of words in more buckets
-          .map(|v| v.len().saturating_pow(4))
-          .sum::<usize>()
-      );
-
-      // prefer words without repeated letters
-      possible_tiebreakers.sort_by_cached_key(|(w, _)| !w.is_unique());
-
-      let mut om_buf = Vec::with_capacity(self.candidates.len());
-      om_buf.par_extend(grade_many(&self.candidates[0..1], self.candidates.as_slice()).map(|(_, _, x)| x));
-
-      let mut it = om_buf.into_iter();
-      let mut organic_mappings = FeedbackMap::with_capacity(8);
-      for word in self.candidates.iter().copied() {
-        let encoding = it.next().unwrap();
-        organic_mappings.get_or_insert_with(encoding, || Vec::with_capacity(8))
-          .push(word);
+  /// Everything learned so far as a short code to paste somewhere, e.g. `__A_E/R14T2/QWX/E11`
+  ///
+  /// The `/`-separated parts are the greens by position (`_` where unknown), each yellow followed by the positions
+  /// it isn't in, the greys, and each letter followed by its fewest and most copies where the other parts don't
+  /// already imply those. Empty parts are `-`. Read back with [`Self::import_constraints`].
+  pub fn export_constraints(&self) -> String {
+    use std::fmt::Write;
+    let state = self.export_state();
+    let or_dash = |part: String| if part.is_empty() { "-".to_string() } else { part };
+    let greens = state.confirmed.iter()
+      .map(|ch| ch.map_or('_', |ch| ch.to_u8() as char))
+      .collect();
+    let mut yellows = String::new();
+    for RequiredLetter { letter, not_at } in &state.required {
+      write!(yellows, "{letter}").unwrap();
+      for i in not_at {
+        write!(yellows, "{}", i + 1).unwrap();
       }
-      let organic_mappings = (self.candidates[0], organic_mappings);
+    }
+    let greys = state.excluded.iter().map(|ch| ch.to_u8() as char).collect();
+    let mut counts = String::new();
+    for LetterCount { letter, min, max } in state.counts {
+      // a grey has none, and a green or yellow has at least as many as it shows
+      let shown = (state.confirmed.iter().filter(|&&ch| ch == Some(letter)).count() as u8)
+        .max(state.required.iter().any(|r| r.letter == letter).into());
+      if !state.excluded.contains(&letter) && (min > shown || max < 5) {
+        write!(counts, "{letter}{min}{max}").unwrap();
+      }
+    }
+    [greens, yellows, greys, counts].map(or_dash).join("/")
+  }
 
-      if OPTIONS.get().unwrap().is_verbose {
-        fn tiebreaker_printout((word, mapping): &(Word, FeedbackMap<Vec<Word>>)) {
-          println!(" {word}");
-          for (encoding, words) in mapping.entries() {
-            print!("  {encoding} -");
-            for w in words {
-              print!(" {w}");
-            }
-            println!();
-          }
-        }
+  /// Replace everything learned so far with a code from [`Self::export_constraints`], in either case
+  ///
+  /// Candidates are refilled from the answer list, call [`Self::prune`] to narrow them back down.
+  pub fn import_constraints(&mut self, code: &str) -> Result<(), String> {
+    let upper = code.trim().to_ascii_uppercase();
+    let parts = upper.split('/').map(|part| if part == "-" { "" } else { part }).collect::<Vec<_>>();
+    let [greens, yellows, greys, counts] = parts[..] else {
+      return Err(format!("constraints `{code}` should have 4 parts separated by `/`"));
+    };
+    let letter = |b: u8| Letter::from_u8(b).ok_or_else(|| format!("unknown symbol `{}` in constraints `{code}`", b as char));
+    let digit = |b: u8| if b.is_ascii_digit() { Ok(b - b'0') } else { Err(format!("expected a digit in constraints `{code}`, found `{}`", b as char)) };
 
-        println!("upcoming organic guess:");
-        tiebreaker_printout(&organic_mappings);
-        println!("possible tiebreakers:");
-        for tb in possible_tiebreakers.iter().take(5) {
-          tiebreaker_printout(tb);
+    let mut state = GuesserState::default();
+    if greens.len() != 5 {
+      return Err(format!("constraints `{code}` should start with 5 greens, found {}", greens.len()));
+    }
+    for (slot, b) in state.confirmed.iter_mut().zip(greens.bytes()) {
+      *slot = if b == b'_' { None } else { Some(letter(b)?) };
+    }
+    for b in yellows.bytes() {
+      if b.is_ascii_digit() {
+        let position = digit(b)?;
+        let required = state.required.last_mut().ok_or_else(|| format!("position `{position}` in constraints `{code}` comes before any yellow"))?;
+        if !(1..=5).contains(&position) {
+          return Err(format!("position `{position}` in constraints `{code}` is out of range"));
         }
+        required.not_at.push(usize::from(position) - 1);
+      } else {
+        state.required.push(RequiredLetter { letter: letter(b)?, not_at: Vec::new() });
       }
+    }
+    state.excluded = greys.bytes().map(letter).collect::<Result<_, _>>()?;
+    let counts = counts.as_bytes();
+    if counts.len() % 3 != 0 {
+      return Err(format!("copy counts in constraints `{code}` should each be a letter and two digits"));
+    }
+    for count in counts.chunks(3) {
+      state.counts.push(LetterCount { letter: letter(count[0])?, min: digit(count[1])?, max: digit(count[2])? });
+    }
+    self.import_state(&state)
+  }
+}
 
-      let possible_tiebreakers = possible_tiebreakers.iter();
+impl<const N: usize> Guesser<N> {
+  /// With fewer than 3 candidates, guessing them is at least as good as any burner.
+  /// Past 26, a single guess is unlikely to split them all apart: it has at most
+  /// [`WordFeedback::COMBINATIONS`] grades, and real words only ever get a small fraction of those.
+  pub const DEFAULT_TIEBREAK_WINDOW: RangeInclusive<usize> = 3..=26;
 
-      let (_, organic_mappings) = organic_mappings;
+  /// A prior only reorders this few candidates; before that, covering letters matters more than being likely.
+  pub const PRIOR_WINDOW: usize = 26;
 
-      possible_tiebreakers
-        // only check the best tiebreaker candidates
-        .take(5)
-        // compare the narrowing of the tiebreaker to that of the first candidate.
-        // only use a tiebreaker if guaranteed to actually provide an advantage
-        .find_map(|(tiebreaker, mapping)| {
-          use std::cmp::Ordering;
-          match mapping.len().cmp(&organic_mappings.len()) {
-            // fewer buckets than organic; guaranteed less potent
-            Ordering::Less => false,
+  /// Any number of copies of a letter, as far as we know
+  const ANY_COUNT: (u8, u8) = (0, N as u8);
 
-            // more buckets than organic; guaranteed more potent
-            Ordering::Greater => true,
+  /// Solve for one of `answers`, suggesting any of `guesses` to narrow them down
+  pub fn new_with_lists(answers: &'static [Word<N>], guesses: &'static [Word<N>]) -> Self {
+    Self::with_buffer(Vec::new(), answers, guesses)
+  }
 
-            // compare potency
-            Ordering::Equal => {
-              match mapping.values().map(|v| v.len()).max().cmp(&organic_mappings.values().map(|v| v.len()).max()) {
-                // worst case has better chance than for organic
-                Ordering::Less => true,
+  fn with_buffer(mut candidates_buf: Vec<Word<N>>, answers: &'static [Word<N>], guesses: &'static [Word<N>]) -> Self {
+    candidates_buf.clear();
+    candidates_buf.extend_from_slice(answers);
+    Self {
+      candidates: candidates_buf,
+      excluded: LetterSet::default(),
+      required: [Positions::empty(); 26],
+      confirmed: [const { None }; N],
+      counts: [Self::ANY_COUNT; 26],
+      answers,
+      blocked: Vec::new(),
+      history: Vec::new(),
+      opener: None,
+      guesses,
+      is_hardmode: false,
+      strategy: Strategy::Frequency,
+      ranking: Ranking::Positional,
+      max_turns: 6,
+      tiebreak_window: Self::DEFAULT_TIEBREAK_WINDOW,
+      is_tiebreak: true,
+      is_verbose: false,
+      threads: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+      matrix: None,
+      prior: None,
+      has_burner: false,
+      skipped: 0,
+      observer: Box::new(()),
+      grades: Vec::new(),
+    }
+  }
 
-                // worst case has worse chance than for organic
-                Ordering::Greater => false,
+  pub fn set_hardmode(&mut self, is_hardmode: bool) {
+    self.is_hardmode = is_hardmode;
+  }
 
-                // last chance to prove yourself:
-                // same number of buckets, same worst case, who has a better average case?
-                // (don't need to divide because denominator is shared)
-                Ordering::Equal => mapping.values().map(|v| v.len()).sum::<usize>() < organic_mappings.values().map(|v| v.len()).sum::<usize>(),
-              }
-            }
-          }.then_some(*tiebreaker)
-        })
-    })
+  pub fn set_strategy(&mut self, strategy: Strategy) {
+    self.strategy = strategy;
   }
 
-  pub fn prune(&mut self, turn: u32) {
-    let include = |word: &Word| -> bool {
-      // Must contain all confirmed
-      word.iter().copied().zip(self.confirmed.iter().copied())
-        .all(|(a, b)| b.is_none_or(|b| a == b))
-      &&
-      // Must contain none excluded
-      !word.iter().any(|ch| self.excluded.binary_search(ch).is_ok())
-      &&
-      // Must contain all required
-      self.required.iter().copied().all(|(r, p)| {
-        word.contains(&r) &&
-        word.iter().copied()
-          .enumerate()
-          // but only in an open space
-          .filter(|&(i, ch)| self.confirmed[i].is_none() && ch == r)
-          // where that character has not been tried yet
-          .all(|(i, _)| !p.contains(Positions::from_index(i).unwrap()))
-      })
-    };
+  /// Order candidates with `ranking` instead of [`Ranking::Positional`]
+  ///
+  /// Should be set before any feedback is analyzed.
+  pub fn set_ranking(&mut self, ranking: Ranking) {
+    self.ranking = ranking;
+    if std::mem::take(&mut self.has_burner) {
+      _ = self.candidates.remove(0);
+    }
+    self.rank_fresh_candidates();
+  }
 
-    self.candidates.retain(include);
-    sort_by_frequency(&mut self.candidates);
+  pub fn set_max_turns(&mut self, max_turns: u32) {
+    self.max_turns = max_turns;
+  }
 
-    if turn < 6 && matches!(self.candidates.len(), 3..=26) { // WordFeedback::COMBINATIONS
-      if let Some(tiebreaker) = self.encode_burner() {
-        verbose_println!("tiebreaker: {tiebreaker}");
-        self.candidates.insert(0, tiebreaker);
-      }
-    }
+  /// Only look for a burner when the number of candidates is in `window`
+  ///
+  /// Defaults to [`Self::DEFAULT_TIEBREAK_WINDOW`]. An empty range disables burners under [`Strategy::Frequency`].
+  pub fn set_tiebreak_window(&mut self, window: RangeInclusive<usize>) {
+    self.tiebreak_window = window;
+  }
+
+  /// Whether burners may be suggested at all, under any strategy
+  ///
+  /// With `false`, every suggestion could be the answer, for comparing how games go without the tiebreaker.
+  pub fn set_tiebreak(&mut self, is_tiebreak: bool) {
+    self.is_tiebreak = is_tiebreak;
+  }
+
+  /// Print why candidates are ruled out and how suggestions are chosen. Off by default.
+  pub fn set_verbose(&mut self, is_verbose: bool) {
+    self.is_verbose = is_verbose;
+  }
+
+  /// Tell `observer` about each decision from now on, replacing the previous observer
+  pub fn set_observer(&mut self, observer: Box<dyn Observer<N> + Send>) {
+    self.observer = observer;
+  }
+
+  /// Suggest `opener` on the first turn of every game, starting with this one
+  ///
+  /// Should be set before any feedback is analyzed.
+  pub fn set_opener(&mut self, opener: Word<N>) {
+    self.opener = Some(opener);
+    self.promote(opener);
+  }
+
+  /// Put the most common words first once few candidates remain
+  ///
+  /// `prior` must be sorted alphabetically, as from [`parse_prior`].
+  pub fn set_prior(&mut self, prior: &'static [(Word<N>, u64)]) {
+    assert!(prior.is_sorted_by_key(|&(word, _)| word), "prior must be sorted alphabetically");
+    self.prior = Some(prior);
+  }
+
+  pub fn set_threads(&mut self, threads: NonZeroUsize) {
+    self.threads = threads;
+  }
+
+  /// Start a new game, keeping the configuration and the candidate allocation
+  pub fn reset(&mut self) {
+    self.candidates.clear();
+    self.candidates.extend_from_slice(self.answers);
+    self.excluded = LetterSet::default();
+    self.required = [Positions::empty(); 26];
+    self.confirmed = [const { None }; N];
+    self.counts = [Self::ANY_COUNT; 26];
+    self.blocked.clear();
+    self.history.clear();
+    self.has_burner = false;
+    self.skipped = 0;
+    self.rank_fresh_candidates();
+  }
+
+  /// The answer list is already in [`Ranking::Positional`] order, so only re-rank it for other rankings
+  fn rank_fresh_candidates(&mut self) {
+    if self.ranking != Ranking::Positional {
+      self.ranking.sort(&mut self.candidates);
+    }
+    if let Some(opener) = self.opener {
+      self.promote(opener);
+    }
+  }
+
+  /// Forget the most recently analyzed feedback, which was given on `turn`
+  ///
+  /// The candidates are pruned again from the full answer list as of the turn before.
+  /// Returns `false` if there was nothing to undo.
+  pub fn undo(&mut self, turn: u32) -> bool {
+    let Some(known) = self.history.pop() else {
+      return false;
+    };
+    (self.excluded, self.required, self.confirmed, self.counts) = known;
+    self.candidates.clear();
+    self.candidates.extend_from_slice(self.answers);
+    self.has_burner = false;
+    self.skipped = 0;
+    if turn > 1 {
+      self.prune(turn - 1);
+    } else {
+      let blocked = &self.blocked;
+      self.candidates.retain(|word| blocked.binary_search(word).is_err());
+      self.rank_fresh_candidates();
+    }
+    true
+  }
+
+  /// Rule out `word` as the solution and never suggest it again this game, even as a tiebreaker
+  pub fn exclude_word(&mut self, word: Word<N>) {
+    if let Err(pos) = self.blocked.binary_search(&word) {
+      self.blocked.insert(pos, word);
+    }
+    if self.has_burner && self.candidates.first() == Some(&word) {
+      self.has_burner = false;
+    }
+    self.candidates.retain(|w| w != &word);
+  }
+
+  fn is_blocked(&self, word: &Word<N>) -> bool {
+    self.blocked.binary_search(word).is_ok()
+  }
+
+  pub fn guess(&self) -> Option<&Word<N>> {
+    self.candidates.get(self.skipped)
+  }
+
+  /// Like [`Self::guess`], along with why it was suggested
+  pub fn suggest(&self) -> Option<Suggestion<N>> {
+    let &word = self.guess()?;
+    Some(Suggestion {
+      word,
+      is_burner: self.burner().is_some(),
+      candidates_remaining: self.candidates.len() - usize::from(self.has_burner),
+      expected_remaining: self.expected_remaining(word),
+    })
+  }
+
+  /// Pass on the current suggestion and suggest the next best one instead, until the next feedback
+  ///
+  /// Returns [`None`] without skipping if there's nothing left to suggest.
+  pub fn skip(&mut self) -> Option<&Word<N>> {
+    if self.skipped + 1 >= self.candidates.len() {
+      return None;
+    }
+    self.skipped += 1;
+    self.guess()
+  }
+
+  /// The `n` best suggestions that haven't been skipped, best first
+  ///
+  /// The first may be a burner; see [`Self::burner`].
+  pub fn suggestions(&self, n: usize) -> &[Word<N>] {
+    let rest = &self.candidates[self.skipped.min(self.candidates.len())..];
+    &rest[..n.min(rest.len())]
+  }
+
+  /// The current suggestion, if it was chosen only to narrow down the candidates and can't be the answer
+  pub fn burner(&self) -> Option<&Word<N>> {
+    (self.has_burner && self.skipped == 0).then(|| &self.candidates[0])
+  }
+
+  /// Every word that may be the solution, in the order it was given, before anything was learned
+  pub fn answers(&self) -> &[Word<N>] {
+    self.answers
+  }
+
+  pub fn candidates(&self) -> &[Word<N>] {
+    &self.candidates
+  }
+
+  fn confirm(&mut self, idx: usize, ch: Letter) -> Result<(), Contradiction> {
+    match self.confirmed[idx] {
+      Some(c) if c != ch => return Err(Contradiction::ConfirmedConflict { position: idx, letters: (c, ch) }),
+      Some(_) => return Ok(()),
+      None => {}
+    }
+    if self.excluded.contains(ch) {
+      return Err(Contradiction::Excluded(ch));
+    }
+    if self.required[ch.index()].contains(Positions::from_index(idx).unwrap()) {
+      return Err(Contradiction::RuledOut { letter: ch, position: idx });
+    }
+    self.confirmed[idx] = Some(ch);
+    verbose_println!(self, "letter '{ch}' is confirmed at position {}", idx + 1);
+    self.observer.on_letter_confirmed(idx, ch);
+    Ok(())
+  }
+
+  /// Mark `ch` as somewhere in the word, but not at `idx`
+  fn rule_out(&mut self, idx: usize, ch: Letter) -> Result<(), Contradiction> {
+    if self.confirmed[idx] == Some(ch) {
+      return Err(Contradiction::RuledOut { letter: ch, position: idx });
+    }
+    // a word can't have more different letters than it has positions
+    if !self.is_required(ch) && self.required_letters().count() >= N {
+      return Err(Contradiction::Unplaceable(ch));
+    }
+    self.required[ch.index()].insert(Positions::from_index(idx).unwrap());
+    Ok(())
+  }
+
+  fn is_required(&self, ch: Letter) -> bool {
+    !self.required[ch.index()].is_empty()
+  }
+
+  /// Each letter known to be in the word by being ruled out of somewhere, with where, alphabetically
+  fn required_letters(&self) -> impl Iterator<Item = (Letter, Positions)> + '_ {
+    Letter::iter().zip(self.required)
+      .filter(|(_, p)| !p.is_empty())
+  }
+
+  /// The candidates that fit `pattern`, one symbol per position: a letter it must be, or `?`, `.` or `_` for any letter
+  ///
+  /// Letters are case-insensitive. A burner is never included, since it can't be the answer.
+  pub fn matching(&self, pattern: &str) -> Result<Vec<Word<N>>, String> {
+    let symbols = pattern.chars().collect::<Vec<_>>();
+    if symbols.len() != N {
+      return Err(format!("pattern `{pattern}` has {} symbols, expected {N}", symbols.len()));
+    }
+    let mut letters = [None; N];
+    for (letter, ch) in letters.iter_mut().zip(symbols) {
+      *letter = match ch {
+        '?' | '.' | '_' => None,
+        _ => Some(u8::try_from(ch.to_ascii_uppercase()).ok().and_then(Letter::from_u8)
+          .ok_or_else(|| format!("unknown symbol `{ch}` in pattern `{pattern}`, expected a letter or `?`"))?),
+      };
+    }
+    Ok(self.candidates[usize::from(self.has_burner)..].iter()
+      .copied()
+      .filter(|word| word.iter().zip(&letters).all(|(ch, letter)| letter.is_none_or(|letter| *ch == letter)))
+      .collect())
+  }
+
+  /// Every letter known to be in the word, as many times as it's known to appear, alphabetically
+  pub fn known_letters(&self) -> Vec<Letter> {
+    let mut copies = self.counts.map(|(min, _)| min);
+    for (ch, _) in self.required_letters() {
+      copies[ch.index()] = copies[ch.index()].max(1);
+    }
+    for ch in self.confirmed.iter().flatten() {
+      let confirmed = self.confirmed.iter().filter(|&&c| c == Some(*ch)).count() as u8;
+      copies[ch.index()] = copies[ch.index()].max(confirmed);
+    }
+    Letter::iter()
+      .flat_map(|ch| std::iter::repeat_n(ch, copies[ch.index()].into()))
+      .collect()
+  }
+
+  /// Positions without a confirmed letter
+  pub fn open_positions(&self) -> Positions {
+    Positions::from_iter(
+      self.confirmed.iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_none())
+        .map(|(i, _)| Positions::from_index(i).unwrap())
+    )
+  }
+
+  /// If there are only as many possible spaces as copies still to place, treat them all as confirmed
+  ///
+  /// The letter stays required so its ruled-out positions keep applying.
+  ///
+  /// Returns `true` if an unknown was confirmed
+  fn pidgeon(&mut self, ch: Letter) -> Result<bool, Contradiction> {
+    let p = self.required[ch.index()];
+    let confirmed_copies = self.confirmed.iter().filter(|&&c| c == Some(ch)).count() as u32;
+    // a required letter has at least one copy, even before its count is updated
+    let min = u32::from(self.counts[ch.index()].0.max(1));
+    let unplaced = min.saturating_sub(confirmed_copies);
+    let possible_positions = self.open_positions().difference(p);
+    let num_possible = possible_positions.bits().count_ones();
+    if num_possible < unplaced {
+      return Err(Contradiction::Unplaceable(ch));
+    }
+    if unplaced == 0 {
+      return Ok(false);
+    }
+    verbose_println!(self, "letter '{ch}' can only be placed in {possible_positions}");
+    if num_possible == unplaced {
+      verbose_println!(self, "letter '{ch}' must fill {possible_positions}");
+      for position in possible_positions.iter() {
+        self.confirm(position.into_index(), ch)?;
+      }
+      Ok(true)
+    } else {
+      Ok(false)
+    }
+  }
+
+  /// Learn from the feedback on a played word
+  ///
+  /// If the feedback contradicts what's already known, nothing is learned and the guesser is left as it was.
+  pub fn analyze(&mut self, chars: [(Letter, LetterFeedback); N]) -> Result<Analyzed, Contradiction> {
+    let known = (self.excluded, self.required, self.confirmed, self.counts);
+    if let Err(e) = self.learn(chars) {
+      (self.excluded, self.required, self.confirmed, self.counts) = known;
+      return Err(e);
+    }
+    if self.history.len() >= self.max_turns as usize {
+      self.history.remove(0);
+    }
+    self.history.push(known);
+
+    self.skipped = 0;
+    if std::mem::take(&mut self.has_burner) {
+      _ = self.candidates.remove(0);
+    }
+
+    let solved = chars.iter().all(|&(_, stat)| stat == LetterFeedback::Confirmed);
+    if !solved {
+      let word_used = Word(chars.map(|(c, _)| c));
+      if let Some(pos) = self.candidates.iter().position(|word| word == &word_used) {
+        _ = self.candidates.remove(pos);
+      } // else: user-provided word
+    }
+    Ok(Analyzed { solved })
+  }
+
+  fn learn(&mut self, chars: [(Letter, LetterFeedback); N]) -> Result<(), Contradiction> {
+    for (i, (ch, stat)) in chars.into_iter().enumerate() {
+      match stat {
+        LetterFeedback::Excluded if chars.iter().any(|&(c, s)| c == ch && s != LetterFeedback::Excluded) => {
+          // another copy of the letter was accounted for, so this only rules out the position
+          self.rule_out(i, ch)?;
+          verbose_println!(self, "letter '{ch}' has no more copies, and is not at position {}", i + 1);
+        }
+
+        LetterFeedback::Excluded => {
+          if self.confirmed.contains(&Some(ch)) || self.is_required(ch) {
+            return Err(Contradiction::Excluded(ch));
+          }
+          if !self.excluded.contains(ch) {
+            if self.excluded.len() >= 26 - N {
+              return Err(Contradiction::TooManyExcluded);
+            }
+            self.excluded.insert(ch);
+            verbose_println!(self, "letter '{ch}' is not in the word");
+            self.observer.on_letter_excluded(ch);
+          }
+        }
+
+        LetterFeedback::Required => {
+          if self.excluded.contains(ch) {
+            return Err(Contradiction::Excluded(ch));
+          }
+          self.rule_out(i, ch)?;
+          verbose_println!(self, "letter '{ch}' is required but cannot be in {}", self.required[ch.index()]);
+          _ = self.pidgeon(ch)?;
+        }
+
+        // keep any required entry for the letter, its ruled-out positions still apply
+        LetterFeedback::Confirmed => self.confirm(i, ch)?,
+      }
+    }
+
+    for &(ch, _) in &chars {
+      // every green or yellow copy is in the word, and a grey copy means there are no more
+      let shown = chars.iter().filter(|&&(c, s)| c == ch && s != LetterFeedback::Excluded).count() as u8;
+      let is_capped = chars.iter().any(|&(c, s)| c == ch && s == LetterFeedback::Excluded);
+      let (min, max) = &mut self.counts[ch.index()];
+      *min = (*min).max(shown);
+      if is_capped {
+        *max = (*max).min(shown);
+      }
+      if min > max {
+        return Err(Contradiction::CountConflict(ch));
+      }
+    }
+
+    verbose_println!(self, "draining...");
+    'outer: loop {
+      for ch in Letter::iter() {
+        if self.is_required(ch) && self.pidgeon(ch)? {
+          continue 'outer;
+        }
+      }
+      break;
+    }
+    self.check_consistency()?;
+    verbose_println!(self, "feedback complete: {self}");
+    Ok(())
+  }
+
+  /// No letter may be both excluded and in the word, or every candidate would be pruned
+  fn check_consistency(&self) -> Result<(), Contradiction> {
+    self.excluded.iter()
+      .find(|&ch| self.confirmed.contains(&Some(ch)) || self.is_required(ch))
+      .map_or(Ok(()), |ch| Err(Contradiction::Excluded(ch)))
+  }
+
+  /// Partition the candidates by the feedback each allowed guess would receive
+  fn bucket_guesses(&mut self) -> Vec<(Word<N>, FeedbackMap<Vec<Word<N>>, N>)> {
+    // Pretend the candidate IS the actual word.
+    // If that were the case, how would our guess be judged?
+    let columns = self.matrix.and_then(|matrix|
+      self.candidates.iter()
+        .map(|word| matrix.column(word))
+        .collect::<Option<Vec<_>>>()
+        .map(|columns| (matrix, columns))
+    );
+    let mut buf = std::mem::take(&mut self.grades);
+    buf.clear();
+    if let Some((matrix, columns)) = columns {
+      buf.extend((0..self.guesses.len()).flat_map(|row| columns.iter().map(move |&col| matrix.get(row, col))));
+    } else {
+      buf.resize(self.guesses.len()*self.candidates.len(), WordFeedback::new([LetterFeedback::Excluded; N]));
+      grade_many_with_threads_unchecked(self.guesses, self.candidates.as_slice(), &mut buf, self.threads);
+    }
+
+    let mut out = Vec::with_capacity(self.guesses.len());
+    let mut mapping = DenseFeedbackMap::new();
+    for (i, guess) in self.guesses.iter().copied().enumerate() {
+      if self.is_blocked(&guess) {
+        continue;
+      }
+      for (j, word) in self.candidates.iter().copied().enumerate() {
+        let encoding = buf[i * self.candidates.len() + j];
+        mapping.get_or_insert_with(encoding, || Vec::with_capacity(8))
+          .push(word);
+      }
+      out.push((guess, mapping.take()));
+    }
+    self.grades = buf;
+    out
+  }
+
+  /// Average number of candidates left after playing `guess`, weighted by how likely each outcome is
+  ///
+  /// Each feedback bucket of size `k` is hit with probability `k/total`, so this is `Σk²/total`.
+  pub fn expected_remaining(&self, guess: Word<N>) -> f64 {
+    let candidates = &self.candidates[usize::from(self.has_burner)..];
+    if candidates.is_empty() {
+      return 0.0;
+    }
+    let mut sizes = DenseFeedbackMap::new();
+    for (_, _, encoding) in grade_many_iter(&[guess], candidates).collect::<Vec<_>>() {
+      *sizes.get_or_insert_with(encoding, || 0usize) += 1;
+    }
+    sizes.values().map(|&k| (k*k) as f64).sum::<f64>() / candidates.len() as f64
+  }
+
+  /// Expected number of bits of information playing `guess` would reveal
+  ///
+  /// The current candidates are the probability space, each equally likely to be the solution.
+  /// `guess` can be any word, not just one of the allowed guesses.
+  pub fn information_gain(&self, guess: Word<N>) -> f64 {
+    let candidates = &self.candidates[usize::from(self.has_burner)..];
+    entropy(&self.partition(guess), candidates.len())
+  }
+
+  /// The current candidates grouped by the feedback they would give `guess`, in feedback order
+  ///
+  /// Each group is what would remain after playing `guess` and getting that feedback.
+  pub fn feedback_buckets(&self, guess: Word<N>) -> Vec<(WordFeedback<N>, Vec<Word<N>>)> {
+    self.partition(guess).into_entries()
+  }
+
+  fn partition(&self, guess: Word<N>) -> FeedbackMap<Vec<Word<N>>, N> {
+    let candidates = &self.candidates[usize::from(self.has_burner)..];
+    let mut mapping = DenseFeedbackMap::new();
+    for (_, word, encoding) in grade_many_iter(&[guess], candidates).collect::<Vec<_>>() {
+      mapping.get_or_insert_with(encoding, Vec::new).push(word);
+    }
+    mapping.take()
+  }
+
+  /// The `count` guesses that reveal the most bits of information about the candidates, best first
+  ///
+  /// Every allowed guess is graded against every candidate, so from the start of a game with the
+  /// full dictionaries this takes a while; a feedback matrix set with `set_feedback_matrix` saves regrading.
+  pub fn best_openers(&self, count: usize) -> Vec<(Word<N>, f64)> {
+    /// Guesses graded at once, to keep the buffer small
+    const CHUNK: usize = 256;
+    let candidates = &self.candidates[usize::from(self.has_burner)..];
+    if candidates.is_empty() {
+      return Vec::new();
+    }
+    let bits = |grades: &mut dyn Iterator<Item = WordFeedback<N>>| -> f64 {
+      let mut sizes = vec![0usize; WordFeedback::<N>::COMBINATIONS];
+      for grade in grades {
+        sizes[grade.to_index() as usize] += 1;
+      }
+      let total = candidates.len() as f64;
+      sizes.into_iter()
+        .filter(|&k| k > 0)
+        .map(|k| {
+          let p = k as f64 / total;
+          -p * p.log2()
+        })
+        .sum()
+    };
+    let columns = self.matrix.and_then(|matrix|
+      candidates.iter()
+        .map(|word| matrix.column(word))
+        .collect::<Option<Vec<_>>>()
+        .map(|columns| (matrix, columns))
+    );
+    let mut scores = Vec::with_capacity(self.guesses.len());
+    if let Some((matrix, columns)) = columns {
+      for (row, &guess) in self.guesses.iter().enumerate() {
+        scores.push((guess, bits(&mut columns.iter().map(|&col| matrix.get(row, col)))));
+      }
+    } else {
+      let mut buf = vec![WordFeedback::new([LetterFeedback::Excluded; N]); CHUNK*candidates.len()];
+      for chunk in self.guesses.chunks(CHUNK) {
+        let buf = &mut buf[..chunk.len()*candidates.len()];
+        grade_many_with_threads_unchecked(chunk, candidates, buf, self.threads);
+        for (&guess, grades) in chunk.iter().zip(buf.chunks(candidates.len())) {
+          scores.push((guess, bits(&mut grades.iter().copied())));
+        }
+      }
+    }
+    // stable, so ties stay in dictionary order
+    scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    scores.truncate(count);
+    scores
+  }
+
+  #[inline(never)]
+  fn encode_burner(&mut self) -> Option<Word<N>> {
+    // the window may include 0, and there's nothing to break a tie between anyway
+    if self.candidates.is_empty() {
+      return None;
+    }
+
+    let mut possible_tiebreakers = self.bucket_guesses();
+
+    // don't bother if the burner would have been just as effective as trying both
+    possible_tiebreakers.retain(|(_, mapping)| mapping.len() > 2);
+
+    if self.is_hardmode {
+      possible_tiebreakers.retain(|(w, _)| self.uses_hints(w));
+    }
+
+    if possible_tiebreakers.is_empty() {
+      return None;
+    }
+
+    // prefer words with fewer letters we already know
+    possible_tiebreakers.sort_by_cached_key(|(w, _)|
+      self.excluded.iter()
+        .chain(self.required_letters().map(|(ch, _)| ch))
+        .chain(self.confirmed.iter().copied().flatten())
+        .filter(|ch| w.contains(ch))
+        .count()
+    );
+
+    // prefer words with more tiebreakers
+    possible_tiebreakers.sort_by_key(|(_, m)| usize::MAX - m.len());
+
+    // prefer more potent tiebreakers
+    possible_tiebreakers.sort_by_key(|(_, m)|
+      m.values()
+        // more words in the same bucket are exponentially less valuable than having the same number of words in more buckets
+        .map(|v| v.len().saturating_pow(4))
+        .sum::<usize>()
+    );
+
+    // prefer words without repeated letters
+    possible_tiebreakers.sort_by_cached_key(|(w, _)| !w.is_unique());
+
+    let om_buf = &mut self.grades;
+    om_buf.clear();
+    om_buf.resize(self.candidates.len(), WordFeedback::new([LetterFeedback::Excluded; N]));
+    grade_many(&self.candidates[0..1], self.candidates.as_slice(), om_buf)
+      .expect("there should be candidates to grade");
+
+    let mut organic_mappings = DenseFeedbackMap::new();
+    for (word, &encoding) in self.candidates.iter().copied().zip(om_buf.iter()) {
+      organic_mappings.get_or_insert_with(encoding, || Vec::with_capacity(8))
+        .push(word);
+    }
+    let organic_mappings = (self.candidates[0], organic_mappings.take());
+
+    if self.is_verbose {
+      fn tiebreaker_printout<const N: usize>((word, mapping): &(Word<N>, FeedbackMap<Vec<Word<N>>, N>)) {
+        println!(" {word}");
+        for (encoding, words) in mapping.entries() {
+          print!("  {encoding} -");
+          for w in words {
+            print!(" {w}");
+          }
+          println!();
+        }
+      }
+
+      println!("upcoming organic guess:");
+      tiebreaker_printout(&organic_mappings);
+      println!("possible tiebreakers:");
+      for tb in possible_tiebreakers.iter().take(5) {
+        tiebreaker_printout(tb);
+      }
+    }
+
+    let possible_tiebreakers = possible_tiebreakers.iter();
+
+    let (_, organic_mappings) = organic_mappings;
+
+    possible_tiebreakers
+      // only check the best tiebreaker candidates
+      .take(5)
+      // compare the narrowing of the tiebreaker to that of the first candidate.
+      // only use a tiebreaker if guaranteed to actually provide an advantage
+      .find_map(|(tiebreaker, mapping)| {
+        use std::cmp::Ordering;
+        match mapping.len().cmp(&organic_mappings.len()) {
+          // fewer buckets than organic; guaranteed less potent
+          Ordering::Less => false,
+
+          // more buckets than organic; guaranteed more potent
+          Ordering::Greater => true,
+
+          // compare potency
+          Ordering::Equal => {
+            match mapping.values().map(|v| v.len()).max().cmp(&organic_mappings.values().map(|v| v.len()).max()) {
+              // worst case has better chance than for organic
+              Ordering::Less => true,
+
+              // worst case has worse chance than for organic
+              Ordering::Greater => false,
+
+              // last chance to prove yourself:
+              // same number of buckets, same worst case, who has a better average case?
+              // (don't need to divide because denominator is shared)
+              Ordering::Equal => mapping.values().map(|v| v.len()).sum::<usize>() < organic_mappings.values().map(|v| v.len()).sum::<usize>(),
+            }
+          }
+        }.then_some(*tiebreaker)
+      })
+  }
+
+  /// Reuses every confirmed letter in place and every required letter somewhere it could still be
+  ///
+  /// This is the rule hard mode holds every guess to
+  fn uses_hints(&self, word: &Word<N>) -> bool {
+    self.check_hints(word).is_none()
+  }
+
+  /// The first confirmed or required letter that `word` doesn't reuse
+  fn check_hints(&self, word: &Word<N>) -> Option<RejectReason> {
+    // Must contain all confirmed
+    if let Some((position, (found, expected))) = word.iter().copied().zip(self.confirmed.iter().copied())
+      .enumerate()
+      .find_map(|(i, (a, b))| b.filter(|&b| a != b).map(|b| (i, (a, b))))
+    {
+      return Some(RejectReason::WrongConfirmed { position, expected, found });
+    }
+    // Must contain all required
+    let open = self.open_positions();
+    self.required_letters().find_map(|(r, p)| {
+      if !word.contains(&r) {
+        return Some(RejectReason::MissingRequired(r));
+      }
+      // but only in an open space where that character has not been tried yet
+      p.intersection(open).iter()
+        .map(Positions::into_index)
+        .find(|&i| word[i] == r)
+        .map(|position| RejectReason::ForbiddenPosition { letter: r, position })
+    })
+  }
+
+  /// Whether `word` fits everything learned so far, the check [`Guesser::prune`] keeps candidates by
+  ///
+  /// `word` doesn't have to be a candidate, or in either word list.
+  pub fn is_consistent(&self, word: Word<N>) -> bool {
+    self.explain_rejection(word).is_none()
+  }
+
+  /// Why `word` isn't (or wouldn't be, after the next prune) a candidate, if it isn't
+  ///
+  /// Checks the same constraints as [`Guesser::prune`] in the same order and reports the first that fails.
+  pub fn explain_rejection(&self, word: Word<N>) -> Option<RejectReason> {
+    self.check_hints(&word)
+      .or_else(|| self.is_blocked(&word).then_some(RejectReason::Blocked))
+      // Must contain none excluded
+      .or_else(|| word.iter().copied().find(|&ch| self.excluded.contains(ch)).map(RejectReason::Excluded))
+      // Must have as many of each letter as feedback allows, including letters it doesn't have at all
+      .or_else(|| {
+        let counts = word.letter_counts();
+        Letter::iter().find_map(|letter| {
+          let (min, max) = self.counts[letter.index()];
+          let found = counts[letter.index()];
+          (found < min || found > max).then_some(RejectReason::WrongCount { letter, found, min, max })
+        })
+      })
+  }
+
+  /// Suggest whichever guess best partitions the candidates according to the strategy
+  ///
+  /// Non-candidates are only considered if `allow_burner` is set.
+  #[inline(never)]
+  fn rank_by_partition(&mut self, allow_burner: bool) {
+    let mut possible_guesses = self.bucket_guesses();
+
+    if self.is_hardmode {
+      possible_guesses.retain(|(w, _)| self.uses_hints(w));
+    }
+
+    let mut sorted_candidates = self.candidates.clone();
+    sorted_candidates.sort_unstable();
+    let mut ranked = possible_guesses.iter()
+      .map(|(w, mapping)| Partition {
+        guess: *w,
+        bits: entropy(mapping, self.candidates.len()),
+        largest: mapping.values().map(Vec::len).max().unwrap_or(0),
+        buckets: mapping.len(),
+        is_candidate: sorted_candidates.binary_search(w).is_ok(),
+      })
+      .filter(|p| allow_burner || p.is_candidate)
+      .collect::<Vec<_>>();
+
+    // prefer guesses that could win outright when otherwise equal
+    let strategy = self.strategy;
+    ranked.sort_by(|a, b|
+      match strategy {
+        Strategy::Minimax => a.largest.cmp(&b.largest).then(b.buckets.cmp(&a.buckets)),
+        _ => b.bits.total_cmp(&a.bits),
+      }.then(b.is_candidate.cmp(&a.is_candidate))
+    );
+
+    if self.is_verbose {
+      println!("best partitioning guesses:");
+      for p in ranked.iter().take(5) {
+        match strategy {
+          Strategy::Minimax => println!(" {} - at most {} left, {} buckets", p.guess, p.largest, p.buckets),
+          _ => println!(" {} - {:.3} bits", p.guess, p.bits),
+        }
+      }
+    }
+
+    let best = ranked.first().map(|p| p.guess);
+
+    if let Some(best) = best {
+      verbose_println!(self, "best partition: {best}");
+      self.promote(best);
+    }
+  }
+
+  /// Make `word` the next suggestion, marking it as a burner if it isn't a candidate
+  fn promote(&mut self, word: Word<N>) {
+    match self.candidates.iter().position(|w| w == &word) {
+      Some(pos) => self.candidates[..=pos].rotate_right(1),
+      None => {
+        self.candidates.insert(0, word);
+        self.has_burner = true;
+        self.observer.on_tiebreaker_chosen(word);
+      }
+    }
+  }
+
+  /// Drops the candidates that no longer fit and reranks the rest for `turn`
+  pub fn prune(&mut self, turn: u32) -> Pruned {
+    self.skipped = 0;
+    let mut candidates = std::mem::take(&mut self.candidates);
+    let before = candidates.len();
+    candidates.retain(|word| self.is_consistent(*word));
+    let pruned = Pruned { before, after: candidates.len() };
+    self.observer.on_candidates_pruned(before, pruned.after);
+    self.ranking.sort(&mut candidates);
+    if let Some(prior) = self.prior && candidates.len() <= Self::PRIOR_WINDOW {
+      // keeps the frequency order between words that are just as likely
+      candidates.sort_by_cached_key(|word| std::cmp::Reverse(
+        prior.binary_search_by_key(word, |&(w, _)| w).map_or(0, |i| prior[i].1)
+      ));
+    }
+    self.candidates = candidates;
+
+    match self.strategy {
+      Strategy::Frequency => {
+        if self.is_tiebreak && turn < self.max_turns && self.tiebreak_window.contains(&self.candidates.len())
+          && let Some(tiebreaker) = self.encode_burner()
+        {
+          verbose_println!(self, "tiebreaker: {tiebreaker}");
+          self.promote(tiebreaker);
+        }
+      }
+
+      Strategy::Entropy | Strategy::Minimax => {
+        if self.candidates.len() > 2 {
+          self.rank_by_partition(self.is_tiebreak && turn < self.max_turns);
+        }
+      }
+    }
+    pruned
+  }
+}
+
+/// Everything learned so far, e.g. `greens: _R__E, yellows: A(not p2), greys: QWX`
+impl<const N: usize> std::fmt::Display for Guesser<N> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("greens: ")?;
+    for ch in self.confirmed {
+      match ch {
+        Some(ch) => write!(f, "{ch}")?,
+        None => f.write_str("_")?,
+      }
+    }
+    f.write_str(", yellows: ")?;
+    if self.required_letters().next().is_none() {
+      f.write_str("-")?;
+    }
+    for (n, (ch, not_at)) in self.required_letters().enumerate() {
+      if n > 0 {
+        f.write_str(" ")?;
+      }
+      write!(f, "{ch}")?;
+      for (i, position) in not_at.iter().enumerate() {
+        write!(f, "{}p{}", if i == 0 { "(not " } else { "," }, position.into_index() + 1)?;
+      }
+      f.write_str(")")?;
+    }
+    f.write_str(", greys: ")?;
+    if self.excluded.is_empty() {
+      f.write_str("-")?;
+    }
+    for ch in self.excluded.iter() {
+      write!(f, "{ch}")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const fn word(s: &[u8; 5]) -> Word {
+    Word::from_bytes(*s).unwrap()
+  }
+
+  fn feedback(w: &[u8; 5], fb: [LetterFeedback; 5]) -> [(Letter, LetterFeedback); 5] {
+    std::array::from_fn(|i| (word(w)[i], fb[i]))
+  }
+
+  #[test]
+  fn test_feedback_index() {
+    let mut seen = [false; WordFeedback::<5>::COMBINATIONS];
+    for index in 0..WordFeedback::<5>::COMBINATIONS as u16 {
+      let fb = WordFeedback::<5>::from_index(index).unwrap();
+      assert_eq!(fb.to_index(), index);
+      assert!(!std::mem::replace(&mut seen[index as usize], true));
+    }
+    assert_eq!(WordFeedback::<5>::from_index(243), None);
+    assert_eq!(WordFeedback::new([LetterFeedback::Excluded; 5]).to_index(), 0);
+    assert_eq!(WordFeedback::new([LetterFeedback::Confirmed; 5]).to_index(), 242);
+    assert_eq!(WordFeedback::new([LetterFeedback::Required, LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Excluded]).to_index(), 1);
+  }
+
+  #[test]
+  fn test_parse_feedback() {
+    use LetterFeedback::*;
+    let expected = WordFeedback::new([Confirmed, Required, Excluded, Excluded, Confirmed]);
+    assert_eq!("+?__+".parse(), Ok(expected));
+    assert_eq!("gybbg".parse(), Ok(expected));
+    assert_eq!("GYBbg".parse(), Ok(expected));
+    assert_eq!("+?__".parse::<WordFeedback>(), Err(FeedbackParseError::WrongLength { expected: 5, found: 4 }));
+    assert_eq!("+?__+_".parse::<WordFeedback>(), Err(FeedbackParseError::WrongLength { expected: 5, found: 6 }));
+    assert_eq!("+?x_+".parse::<WordFeedback>(), Err(FeedbackParseError::UnknownSymbol('x')));
+  }
+
+  #[test]
+  fn test_feedback_symbols() {
+    use LetterFeedback::*;
+    // `o` for a letter in the right spot, like on some other boards
+    let symbols = FeedbackSymbols { input: ['.', '*', 'o'], output: ['.', '*', 'o'] };
+    let expected = WordFeedback::new([Confirmed, Required, Excluded, Excluded, Confirmed]);
+    assert_eq!(symbols.parse("o*..o"), Ok(expected));
+    assert_eq!(symbols.parse("+?_.g"), Ok(expected), "the built-in symbols still work");
+    assert_eq!(symbols.parse::<5>("o*..x"), Err(FeedbackParseError::UnknownSymbol('x')));
+    assert_eq!(symbols.display(expected).to_string(), "o*..o");
+    assert_eq!(expected.to_string(), "🟩🟨⬜⬜🟩", "the default glyphs are unaffected");
+    assert_eq!(FeedbackSymbols::default().glyph(Confirmed), '🟩');
+  }
+
+  #[test]
+  fn test_ascii_palette() {
+    assert!(Palette::Ascii.feedback().iter().all(char::is_ascii));
+    assert!((0..=6).all(|turn| Palette::Ascii.turn_color(turn, 6).is_ascii()));
+    assert!((0..Palette::BAR_WIDTH).all(|i| Palette::Ascii.bar_color(i).is_ascii()));
+    assert!(Palette::Ascii.empty().is_ascii());
+    // the emoji bar gets cooler the longer it is
+    assert_eq!((0..Palette::BAR_WIDTH).map(|i| Palette::Emoji.bar_color(i)).collect::<String>(),
+      "🟥🟥🟥🟥🟥🟥🟧🟧🟧🟧🟧🟧🟧🟨🟨🟨🟨🟨🟨🟨🟨🟩🟩🟩🟩🟩🟩🟩🟩🟦🟦🟦🟦🟦🟦🟦🟪🟪🟪🟪🟪🟪");
+  }
+
+  #[test]
+  fn test_colorblind_palette() {
+    assert_eq!("colorblind".parse(), Ok(Palette::Colorblind));
+    assert_eq!("default".parse(), Ok(Palette::Emoji));
+    assert!("rainbow".parse::<Palette>().is_err());
+    // nothing yellow or green, and every feedback looks different from the default
+    let colorblind = Palette::Colorblind.feedback();
+    assert!(!colorblind.iter().any(|ch| matches!(ch, '🟨' | '🟩')));
+    assert!(!(0..=6).any(|turn| matches!(Palette::Colorblind.turn_color(turn, 6), '🟨' | '🟩')));
+    assert_ne!(colorblind[LetterFeedback::Required as usize], Palette::Emoji.feedback()[LetterFeedback::Required as usize]);
+    assert_ne!(colorblind[LetterFeedback::Confirmed as usize], Palette::Emoji.feedback()[LetterFeedback::Confirmed as usize]);
+    let feedback: WordFeedback = "+?_?+".parse().unwrap();
+    let symbols = FeedbackSymbols { output: colorblind, ..FeedbackSymbols::DEFAULT };
+    assert_eq!(feedback.0.map(|fb| symbols.glyph(fb)).iter().collect::<String>(), "🟧🟦⬜🟦🟧");
+  }
+
+  #[test]
+  fn test_suggestions() {
+    static WORDS: [Word; 4] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")];
+    static GUESSES: [Word; 5] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH"), word(b"HUMBL")];
+    let mut guesser = Guesser::new_with_lists(&WORDS, &GUESSES);
+    for n in 0..6 {
+      assert_eq!(guesser.suggestions(n).len(), n.min(WORDS.len()));
+    }
+    assert_eq!(guesser.burner(), None);
+
+    guesser.set_strategy(Strategy::Entropy);
+    // the burner isn't counted as a candidate that survived
+    assert_eq!(guesser.prune(1), Pruned { before: 4, after: 4 });
+    assert_eq!(guesser.burner(), Some(&word(b"HUMBL")));
+    assert_eq!(guesser.suggestions(1), [word(b"HUMBL")]);
+    let suggestion = guesser.suggest().unwrap();
+    assert_eq!((suggestion.word, suggestion.is_burner, suggestion.candidates_remaining), (word(b"HUMBL"), true, 4));
+    assert_eq!(suggestion.expected_remaining, guesser.expected_remaining(word(b"HUMBL")));
+    for n in 0..7 {
+      assert_eq!(guesser.suggestions(n).len(), n.min(WORDS.len() + 1));
+    }
+
+    // the burner is dropped as soon as feedback comes in, whether or not it was played
+    guesser.analyze(std::array::from_fn(|i| (WORDS[0][i], crate::play::check_word(WORDS[1], WORDS[0])[i]))).unwrap();
+    assert_eq!(guesser.burner(), None);
+    assert!(!guesser.candidates().contains(&word(b"HUMBL")));
+  }
+
+  #[test]
+  fn test_separate_lists() {
+    static ANSWERS: [Word; 3] = [word(b"CRANE"), word(b"CRATE"), word(b"CRAZE")];
+    static GUESSES: [Word; 5] = [word(b"CRANE"), word(b"CRATE"), word(b"CRAZE"), word(b"ZONAL"), word(b"TENTH")];
+    let mut guesser = Guesser::new_with_lists(&ANSWERS, &GUESSES);
+    assert_eq!(guesser.candidates(), &ANSWERS);
+    guesser.analyze(std::array::from_fn(|i| (ANSWERS[0][i], crate::play::check_word(ANSWERS[1], ANSWERS[0])[i]))).unwrap();
+    // the played word was already dropped by analyze
+    assert_eq!(guesser.prune(1), Pruned { before: 2, after: 2 });
+    assert!(guesser.candidates().iter().all(|w| ANSWERS.contains(w) || GUESSES.contains(w)));
+    assert!(!guesser.candidates().contains(&ANSWERS[0]));
+  }
+
+  #[test]
+  fn test_reset() {
+    let play = |guesser: &mut Guesser, answer: Word| -> Vec<Word> {
+      let mut played = Vec::new();
+      for turn in 1..=6 {
+        let guess = *guesser.guess().unwrap();
+        played.push(guess);
+        if guess == answer { break; }
+        let fb = crate::play::check_word(answer, guess);
+        guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+        guesser.prune(turn);
+      }
+      played
+    };
+    let mut reused = Guesser::new(Vec::new());
+    reused.set_hardmode(true);
+    play(&mut reused, word(b"JAZZY"));
+    reused.reset();
+    let mut fresh = Guesser::new(Vec::new());
+    fresh.set_hardmode(true);
+    assert_eq!(reused.candidates(), fresh.candidates());
+    assert_eq!(play(&mut reused, word(b"CRANE")), play(&mut fresh, word(b"CRANE")));
+  }
+
+  #[test]
+  fn test_state_round_trip() {
+    let answer = word(b"SHAKE");
+    let mut guesser = Guesser::new(Vec::new());
+    for turn in 1..=3 {
+      let guess = *guesser.guess().unwrap();
+      if guess == answer { break; }
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(turn);
+    }
+    let state = guesser.export_state();
+    assert!(!state.excluded.is_empty() || !state.required.is_empty() || state.confirmed.iter().any(Option::is_some));
+
+    let mut restored = Guesser::new(Vec::new());
+    restored.import_state(&state).unwrap();
+    assert_eq!(restored.export_state(), state);
+    restored.prune(3);
+    let mut expected = guesser.candidates().to_vec();
+    let mut actual = restored.candidates().to_vec();
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+
+    for i in [5, 7, 9, usize::MAX] {
+      let bad = GuesserState { required: vec![RequiredLetter { letter: Letter::A, not_at: vec![i] }], ..GuesserState::default() };
+      assert!(restored.import_state(&bad).is_err(), "accepted position {i}");
+    }
+  }
+
+  #[test]
+  fn test_import_unplaced_letter() {
+    // in the word, but not ruled out of anywhere yet
+    let unplaced = GuesserState { required: vec![RequiredLetter { letter: Letter::A, not_at: Vec::new() }], ..GuesserState::default() };
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.import_state(&unplaced).unwrap();
+    guesser.prune(1);
+    assert!(!guesser.candidates().is_empty());
+    assert!(guesser.candidates().iter().all(|w| w.contains(&Letter::A)));
+    assert_eq!(guesser.explain_rejection(word(b"THOSE")), Some(RejectReason::WrongCount { letter: Letter::A, found: 0, min: 1, max: 5 }));
+
+    // exported as the count it's stored as, which reads back the same
+    let state = guesser.export_state();
+    assert_eq!(state.counts, [LetterCount { letter: Letter::A, min: 1, max: 5 }]);
+    let mut restored = Guesser::new(Vec::new());
+    restored.import_state(&state).unwrap();
+    assert_eq!(restored.export_state(), state);
+    restored.prune(1);
+    assert_eq!(restored.candidates(), guesser.candidates());
+  }
+
+  #[test]
+  fn test_constraints_round_trip() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let mut guesser = Guesser::new(Vec::new());
+    let guess = word(b"EERIE");
+    guesser.analyze(std::array::from_fn(|i| (guess[i], [Y, B, Y, B, G][i]))).unwrap();
+    let code = guesser.export_constraints();
+    assert_eq!(code, "____E/E12R3/I/E22");
+
+    let mut restored = Guesser::new(Vec::new());
+    restored.import_constraints(&code.to_lowercase()).unwrap();
+    assert_eq!(restored.export_constraints(), code);
+    guesser.prune(1);
+    restored.prune(1);
+    assert_eq!(restored.candidates(), guesser.candidates());
+
+    // a real game, a few turns in
+    let answer = word(b"SHAKE");
+    let mut guesser = Guesser::new(Vec::new());
+    for turn in 1..=3 {
+      let guess = *guesser.guess().unwrap();
+      if guess == answer { break; }
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(turn);
+    }
+    restored.import_constraints(&guesser.export_constraints()).unwrap();
+    restored.prune(3);
+    let mut expected = guesser.candidates().to_vec();
+    let mut actual = restored.candidates().to_vec();
+    expected.sort();
+    actual.sort();
+    assert_eq!(actual, expected);
+
+    restored.import_constraints("_____/-/-/-").unwrap();
+    assert_eq!(restored.export_state(), GuesserState::default());
+    assert_eq!(restored.import_constraints("____E/E1R3/I").unwrap_err(), "constraints `____E/E1R3/I` should have 4 parts separated by `/`");
+    assert_eq!(restored.import_constraints("___E/-/-/-").unwrap_err(), "constraints `___E/-/-/-` should start with 5 greens, found 4");
+    assert_eq!(restored.import_constraints("_____/E6/-/-").unwrap_err(), "position `6` in constraints `_____/E6/-/-` is out of range");
+    assert_eq!(restored.import_constraints("_____/1E/-/-").unwrap_err(), "position `1` in constraints `_____/1E/-/-` comes before any yellow");
+    assert!(restored.import_constraints("E____/-/E/-").is_err());
+  }
+
+  #[test]
+  fn test_expected_remaining() {
+    static WORDS: [Word; 4] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")];
+    let guesser = Guesser::new_with_lists(&WORDS, &WORDS);
+    // buckets of 2, 1, 1
+    assert!((guesser.expected_remaining(word(b"BLAHS")) - 1.5).abs() < 1e-9);
+    // every word in its own bucket
+    assert!((guesser.expected_remaining(word(b"HUMBL")) - 1.0).abs() < 1e-9);
+    // nothing told apart
+    assert!((guesser.expected_remaining(word(b"QUIZZ")) - 4.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_contradictions() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let fresh = || {
+      let mut guesser = Guesser::new(Vec::new());
+      guesser.analyze(feedback(b"CRANE", [G, B, Y, B, B])).unwrap();
+      guesser
+    };
+
+    // R was ruled out, now it's confirmed
+    let mut guesser = fresh();
+    let before = guesser.export_state();
+    assert_eq!(guesser.analyze(feedback(b"CORDS", [G, B, G, B, B])), Err(Contradiction::Excluded(Letter::R)));
+    assert_eq!(guesser.export_state(), before, "a rejected analysis shouldn't change anything");
+    // C was confirmed, now it's ruled out
+    assert_eq!(fresh().analyze(feedback(b"SCAMP", [B, B, Y, B, B])), Err(Contradiction::Excluded(Letter::C)));
+    // A can't be at position 3, and every other position is taken
+    assert_eq!(fresh().analyze(feedback(b"CLOTH", [G, G, Y, G, G])), Err(Contradiction::Unplaceable(Letter::A)));
+    // position 1 is already C
+    assert_eq!(
+      fresh().analyze(feedback(b"SALTY", [G, Y, B, B, B])),
+      Err(Contradiction::ConfirmedConflict { position: 0, letters: (Letter::C, Letter::S) }),
+    );
+    // A was yellow at position 3
+    assert_eq!(
+      fresh().analyze(feedback(b"CHAMP", [G, B, G, B, B])),
+      Err(Contradiction::RuledOut { letter: Letter::A, position: 2 }),
+    );
+    // consistent feedback still goes through
+    assert_eq!(fresh().analyze(feedback(b"CHALK", [G, B, Y, Y, G])), Ok(Analyzed { solved: false }));
+    assert_eq!(fresh().analyze(feedback(b"CLOAK", [G; 5])), Ok(Analyzed { solved: true }));
+  }
+
+  #[test]
+  fn test_excluded_and_required() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.analyze(feedback(b"STOMP", [B, B, Y, B, B])).unwrap();
+    let before = guesser.export_state();
+    // S was grey, now it's yellow
+    let e = guesser.analyze(feedback(b"ROUSE", [B, Y, B, Y, B])).unwrap_err();
+    assert_eq!(e, Contradiction::Excluded(Letter::S));
+    assert_eq!(e.to_string(), "letter 'S' was marked as both in and not in the word");
+    // and now green
+    assert_eq!(guesser.analyze(feedback(b"SOLAR", [G, Y, B, B, B])), Err(Contradiction::Excluded(Letter::S)));
+    assert_eq!(guesser.export_state(), before);
+
+    // a saved state can't smuggle the same conflict in
+    let mut state = before;
+    state.confirmed[0] = Some(Letter::T);
+    assert_eq!(
+      guesser.import_state(&state),
+      Err("letter 'T' was marked as both in and not in the word".to_string()),
+    );
+    assert_eq!(guesser.export_state(), GuesserState::default());
+  }
+
+  #[test]
+  fn test_pidgeon_repeated_letter() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let fresh = || {
+      let mut guesser = Guesser::new(Vec::new());
+      guesser.analyze(feedback(b"EZZOZ", [G, B, B, G, B])).unwrap();
+      guesser
+    };
+
+    // ERROR: one R is green, the other two can only go in the open P2 and P3
+    let mut guesser = fresh();
+    guesser.analyze(feedback(b"RQQRR", [Y, B, B, Y, G])).unwrap();
+    assert_eq!(guesser.to_string(), "greens: ERROR, yellows: R(not p1,p4), greys: QZ");
+    guesser.prune(2);
+    assert_eq!(guesser.candidates(), [word(b"ERROR")]);
+
+    // two Rs to place in three open spaces, so neither is confirmed
+    let mut guesser = fresh();
+    guesser.analyze(feedback(b"RQQRQ", [Y, B, B, Y, B])).unwrap();
+    assert_eq!(guesser.to_string(), "greens: E__O_, yellows: R(not p1,p4), greys: QZ");
+
+    // two Rs but only P5 left for them
+    assert_eq!(fresh().analyze(feedback(b"RAARQ", [Y, G, G, Y, B])), Err(Contradiction::Unplaceable(Letter::R)));
+  }
+
+  #[test]
+  fn test_pidgeon_drain() {
+    use LetterFeedback::{Required as Y, Excluded as B};
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.analyze(feedback(b"EXBCD", [Y, Y, B, B, B])).unwrap();
+    guesser.analyze(feedback(b"XEFGH", [Y, Y, B, B, B])).unwrap();
+    guesser.analyze(feedback(b"IJEXK", [B, B, Y, Y, B])).unwrap();
+    assert_eq!(guesser.to_string(), "greens: _____, yellows: E(not p1,p2,p3) X(not p1,p2,p4), greys: BCDFGHIJK");
+    // X only fits P5, which leaves E only P4, though E comes first and wasn't in this guess
+    guesser.analyze(feedback(b"LMXNO", [B, B, Y, B, B])).unwrap();
+    assert_eq!(guesser.to_string(), "greens: ___EX, yellows: E(not p1,p2,p3) X(not p1,p2,p3,p4), greys: BCDFGHIJKLMNO");
+
+    // a sixth different letter has nowhere to go
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.analyze(feedback(b"ABCDE", [Y; 5])).unwrap();
+    let before = guesser.to_string();
+    assert_eq!(guesser.analyze(feedback(b"FQQQQ", [Y, B, B, B, B])), Err(Contradiction::Unplaceable(Letter::F)));
+    assert_eq!(guesser.to_string(), before);
+
+    // without any ruled out positions, a required letter is kept as a count
+    let state = GuesserState { required: vec![RequiredLetter { letter: Letter::A, not_at: vec![] }], ..GuesserState::default() };
+    guesser.import_state(&state).unwrap();
+    assert_eq!(guesser.known_letters(), [Letter::A]);
+    assert_eq!(guesser.export_state().counts, [LetterCount { letter: Letter::A, min: 1, max: 5 }]);
+  }
+
+  #[test]
+  fn test_exclude_word() {
+    let answer = word(b"SHAKE");
+    let mut guesser = Guesser::new(Vec::new());
+    let blocked = *guesser.guess().unwrap();
+    guesser.exclude_word(blocked);
+    assert!(!guesser.candidates().contains(&blocked));
+    for turn in 1..=5 {
+      let guess = *guesser.guess().unwrap();
+      assert_ne!(guess, blocked, "suggested a blocked word on turn {turn}");
+      if guess == answer { break; }
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(turn);
+      assert!(!guesser.candidates().contains(&blocked));
+    }
+  }
+
+  #[test]
+  fn test_explain_rejection() {
+    let answer = word(b"SHAKE");
+    let mut guesser = Guesser::new(Vec::new());
+    for guess in [word(b"ASHEN"), word(b"SHADE")] {
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(1);
+      for candidate in &guesser.candidates()[guesser.burner().is_some() as usize..] {
+        assert_eq!(guesser.explain_rejection(*candidate), None);
+      }
+      assert_eq!(guesser.explain_rejection(answer), None);
+    }
+    assert_eq!(guesser.explain_rejection(word(b"PHASE")), Some(RejectReason::WrongConfirmed { position: 0, expected: Letter::S, found: Letter::P }));
+    // any word can be checked, in the lists or not
+    assert!(guesser.is_consistent(answer));
+    assert!(guesser.is_consistent(word(b"SHAQE")));
+    assert!(!guesser.is_consistent(word(b"PHASE")));
+    assert_eq!(guesser.explain_rejection(word(b"SHANE")), Some(RejectReason::Excluded(Letter::N)));
+    assert_eq!(guesser.explain_rejection(word(b"SHAME")), None);
+    guesser.exclude_word(word(b"SHAME"));
+    assert_eq!(guesser.explain_rejection(word(b"SHAME")), Some(RejectReason::Blocked));
+
+    let mut guesser = Guesser::new(Vec::new());
+    let guess = word(b"ASHEN");
+    let fb = crate::play::check_word(answer, guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+    assert_eq!(guesser.explain_rejection(word(b"ASHES")), Some(RejectReason::ForbiddenPosition { letter: Letter::A, position: 0 }));
+    assert_eq!(guesser.explain_rejection(word(b"SLATE")), Some(RejectReason::MissingRequired(Letter::H)));
+    assert_eq!(guesser.explain_rejection(word(b"EHSAN")), Some(RejectReason::Excluded(Letter::N)));
+  }
+
+  #[test]
+  fn test_display() {
+    let mut guesser = Guesser::new(Vec::new());
+    assert_eq!(guesser.to_string(), "greens: _____, yellows: -, greys: -");
+    guesser.import_state(&GuesserState {
+      excluded: vec![Letter::Q, Letter::W, Letter::X],
+      required: vec![
+        RequiredLetter { letter: Letter::A, not_at: vec![1] },
+        RequiredLetter { letter: Letter::T, not_at: vec![0, 2] },
+      ],
+      confirmed: [None, Some(Letter::R), None, None, Some(Letter::E)],
+      counts: Vec::new(),
+    }).unwrap();
+    assert_eq!(guesser.to_string(), "greens: _R__E, yellows: A(not p2) T(not p1,p3), greys: QWX");
+  }
+
+  #[test]
+  fn test_tiebreak_window() {
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 1..=0;
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.set_tiebreak_window(empty);
+    for answer in ANSWERS.iter().step_by(ANSWERS.len() / 16) {
+      guesser.reset();
+      for turn in 1..=5 {
+        let guess = *guesser.guess().unwrap();
+        if guess == *answer { break; }
+        let fb = crate::play::check_word(*answer, guess);
+        guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+        guesser.prune(turn);
+        assert_eq!(guesser.burner(), None, "suggested a burner for {answer} on turn {turn}");
+      }
+    }
+  }
+
+  #[test]
+  fn test_tiebreak_window_empty_candidates() {
+    let mut guesser = Guesser::new_with_lists(&[], &ANSWERS[..100]);
+    guesser.set_tiebreak_window(0..=26);
+    guesser.prune(1);
+    assert_eq!(guesser.burner(), None);
+    assert_eq!(guesser.guess(), None);
+  }
+
+  #[test]
+  fn test_no_tiebreak() {
+    for strategy in [Strategy::Frequency, Strategy::Entropy] {
+      let mut guesser = Guesser::new_with_lists(&ANSWERS[..300], &ANSWERS[..600]);
+      guesser.set_strategy(strategy);
+      guesser.set_tiebreak(false);
+      for answer in ANSWERS[..300].iter().step_by(20) {
+        guesser.reset();
+        for turn in 1..=5 {
+          let guess = *guesser.guess().unwrap();
+          if guess == *answer { break; }
+          let fb = crate::play::check_word(*answer, guess);
+          guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+          guesser.prune(turn);
+          assert_eq!(guesser.burner(), None, "{strategy:?} suggested a burner for {answer} on turn {turn}");
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_verbose_is_per_guesser() {
+    let answer = word(b"SHAKE");
+    let mut quiet = Guesser::new_with_lists(&ANSWERS[..300], &ANSWERS[..300]);
+    let mut loud = Guesser::new_with_lists(&ANSWERS[..300], &ANSWERS[..300]);
+    loud.set_verbose(true);
+    assert!(!quiet.is_verbose && loud.is_verbose);
+    for guesser in [&mut quiet, &mut loud] {
+      let guess = word(b"CRANE");
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(1);
+    }
+    assert_eq!(quiet.candidates(), loud.candidates());
+    assert!(!quiet.is_verbose);
+  }
+
+  #[test]
+  fn test_observer() {
+    #[derive(Default)]
+    struct Events(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+    impl Observer for Events {
+      fn on_letter_confirmed(&mut self, idx: usize, letter: Letter) {
+        self.0.lock().unwrap().push(format!("confirmed {letter} at {idx}"));
+      }
+      fn on_letter_excluded(&mut self, letter: Letter) {
+        self.0.lock().unwrap().push(format!("excluded {letter}"));
+      }
+      fn on_candidates_pruned(&mut self, before: usize, after: usize) {
+        self.0.lock().unwrap().push(format!("pruned {before} to {after}"));
+      }
+    }
+
+    let events = Events::default();
+    let log = events.0.clone();
+    let mut guesser = Guesser::new_with_lists(&ANSWERS, &ANSWERS);
+    guesser.set_observer(Box::new(events));
+    let (guess, answer) = (word(b"CRANE"), word(b"SHAKE"));
+    let fb = crate::play::check_word(answer, guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+    let before = guesser.candidates().len();
+    guesser.prune(1);
+    let after = guesser.candidates().len();
+    assert_eq!(*log.lock().unwrap(), [
+      "excluded C".to_string(),
+      "excluded R".to_string(),
+      "confirmed A at 2".to_string(),
+      "excluded N".to_string(),
+      "confirmed E at 4".to_string(),
+      format!("pruned {before} to {after}"),
+    ]);
+
+    // observers only need to be `Send`, not `Sync`
+    struct Unsynced(std::cell::Cell<usize>);
+    impl Observer for Unsynced {
+      fn on_candidates_pruned(&mut self, _before: usize, _after: usize) {
+        self.0.set(self.0.get() + 1);
+      }
+    }
+    guesser.set_observer(Box::new(Unsynced(std::cell::Cell::new(0))));
+    guesser.prune(1);
+  }
+
+  #[test]
+  fn test_positions() {
+    assert_eq!(Positions::ALL.to_string(), "P1,P2,P3,P4,P5");
+    assert_eq!((Positions::P1 | Positions::P3 | Positions::P5).to_string(), "P1,P3,P5");
+    assert_eq!(Positions::empty().to_string(), "-");
+
+    let mut guesser = Guesser::new(Vec::new());
+    assert_eq!(guesser.open_positions().bits(), Positions::ALL.bits());
+    let guess = word(b"CRANE");
+    let fb = crate::play::check_word(word(b"SHAKE"), guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+    assert_eq!(guesser.open_positions().to_string(), "P1,P2,P4");
+    assert_eq!(guesser.known_letters(), [Letter::A, Letter::E]);
+  }
+
+  #[test]
+  fn test_letter_set() {
+    let mut set = LetterSet::default();
+    assert!(set.is_empty());
+    assert!(set.insert(Letter::Z) && set.insert(Letter::A) && set.insert(Letter::M));
+    assert!(!set.insert(Letter::A));
+    assert_eq!(set.len(), 3);
+    assert!(set.contains(Letter::M) && !set.contains(Letter::N));
+    assert_eq!(set.iter().collect::<Vec<_>>(), [Letter::A, Letter::M, Letter::Z]);
+  }
+
+  #[test]
+  fn test_matching() {
+    let guesser = Guesser::new_with_lists(&ANSWERS, &ANSWERS);
+    let found = guesser.matching("?R?_E").unwrap();
+    assert!(found.contains(&word(b"CRANE")) && found.contains(&word(b"TRITE")));
+    assert!(found.iter().all(|w| w[1] == Letter::R && w[4] == Letter::E));
+    assert_eq!(guesser.matching("cr.n.").unwrap(), guesser.matching("CR?N?").unwrap());
+    assert_eq!(guesser.matching("CRANE").unwrap(), [word(b"CRANE")]);
+    assert_eq!(guesser.matching("?????").unwrap().len(), ANSWERS.len());
+    assert!(guesser.matching("QQQQQ").unwrap().is_empty());
+
+    assert_eq!(guesser.matching("CRAN").unwrap_err(), "pattern `CRAN` has 4 symbols, expected 5");
+    assert_eq!(guesser.matching("CR*NE").unwrap_err(), "unknown symbol `*` in pattern `CR*NE`, expected a letter or `?`");
+    assert!(guesser.matching("CRÄNE").is_err());
+  }
+
+  #[test]
+  fn test_dense_feedback_map() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let keys: [WordFeedback; 3] = [[G, B, B, B, Y], [B; 5], [Y, B, B, B, Y]].map(WordFeedback::new);
+    let mut map = DenseFeedbackMap::<u32, 5>::new();
+    for round in 0..2 {
+      for (n, key) in keys.into_iter().enumerate() {
+        *map.get_or_insert_with(key, || 0) += n as u32 + round;
+        *map.get_or_insert_with(key, || 0) += 1;
+      }
+      assert_eq!(map.values().copied().collect::<Vec<_>>(), [1 + round, 2 + round, 3 + round]);
+      assert_eq!(map.entries().map(|(k, _)| k).collect::<Vec<_>>(), [keys[1], keys[2], keys[0]]);
+      let taken = map.take();
+      assert_eq!(taken.len(), 3);
+      assert_eq!(taken.into_entries(), [(keys[1], 2 + round), (keys[2], 3 + round), (keys[0], 1 + round)]);
+      assert_eq!(map.entries().count(), 0);
+    }
+  }
+
+  #[test]
+  fn test_feedback_buckets() {
+    let mut guesser = Guesser::new_with_lists(&ANSWERS, &ANSWERS);
+    let guess = word(b"CRANE");
+    let fb = crate::play::check_word(word(b"SHAKE"), guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+    guesser.prune(1);
+    let candidates = guesser.candidates()[usize::from(guesser.burner().is_some())..].to_vec();
+
+    let probe = word(b"SHALT");
+    let buckets = guesser.feedback_buckets(probe);
+    assert!(buckets.is_sorted_by_key(|(fb, _)| *fb));
+    assert_eq!(buckets.iter().map(|(_, words)| words.len()).sum::<usize>(), candidates.len());
+    for (fb, words) in &buckets {
+      assert!(!words.is_empty());
+      for &w in words {
+        assert_eq!(crate::play::check_word(w, probe), *fb);
+      }
+    }
+    let total = candidates.len() as f64;
+    let bits = buckets.iter().map(|(_, words)| { let p = words.len() as f64 / total; -p * p.log2() }).sum::<f64>();
+    assert!((bits - guesser.information_gain(probe)).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_undo() {
+    let answer = word(b"SHAKE");
+    let mut guesser = Guesser::new(Vec::new());
+    assert!(!guesser.undo(1));
+    let mut states = vec![(guesser.export_state(), guesser.candidates().to_vec())];
+    let mut played = Vec::new();
+    for turn in 1..=3 {
+      let guess = *guesser.guess().unwrap();
+      let fb = crate::play::check_word(answer, guess);
+      let chars = std::array::from_fn(|i| (guess[i], fb[i]));
+      guesser.analyze(chars).unwrap();
+      guesser.prune(turn);
+      played.push(chars);
+      states.push((guesser.export_state(), guesser.candidates().to_vec()));
+    }
+    let sorted = |words: &[Word]| { let mut words = words.to_vec(); words.sort(); words };
+
+    assert!(guesser.undo(3));
+    assert_eq!(guesser.export_state(), states[2].0);
+    assert_eq!(sorted(guesser.candidates()), sorted(&states[2].1));
+    guesser.analyze(played[2]).unwrap();
+    guesser.prune(3);
+    assert_eq!(guesser.export_state(), states[3].0);
+    assert_eq!(sorted(guesser.candidates()), sorted(&states[3].1));
+
+    for turn in (1..=3).rev() {
+      assert!(guesser.undo(turn));
+    }
+    assert!(!guesser.undo(0));
+    assert_eq!(guesser.export_state(), states[0].0);
+    assert_eq!(guesser.candidates(), states[0].1);
+  }
+
+  #[test]
+  fn test_undo_cap() {
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.set_max_turns(2);
+    let answer = word(b"SHAKE");
+    for turn in 1..=3 {
+      let guess = *guesser.guess().unwrap();
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(turn);
+    }
+    assert!(guesser.undo(3));
+    assert!(guesser.undo(2));
+    assert!(!guesser.undo(1));
+  }
+
+  #[test]
+  fn test_letter_counts() {
+    let [eerie, theme] = [word(b"EERIE"), word(b"THEME")];
+    let mut guesser = Guesser::new(Vec::new());
+    // one E is green and one yellow, but the middle one is grey: exactly two
+    let fb = crate::play::check_word(theme, eerie);
+    assert_eq!(fb, WordFeedback::new([LetterFeedback::Required, LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Confirmed]));
+    guesser.analyze(std::array::from_fn(|i| (eerie[i], fb[i]))).unwrap();
+    assert_eq!(guesser.export_state().counts, [
+      LetterCount { letter: Letter::E, min: 2, max: 2 },
+      LetterCount { letter: Letter::I, min: 0, max: 0 },
+      LetterCount { letter: Letter::R, min: 0, max: 0 },
+    ]);
+    assert_eq!(guesser.explain_rejection(theme), None);
+    assert_eq!(guesser.explain_rejection(word(b"THEEE")), Some(RejectReason::WrongCount { letter: Letter::E, found: 3, min: 2, max: 2 }));
+    assert_eq!(guesser.explain_rejection(word(b"ABODE")), Some(RejectReason::WrongCount { letter: Letter::E, found: 1, min: 2, max: 2 }));
+    guesser.prune(1);
+    assert!(guesser.candidates().contains(&theme));
+    assert!(guesser.candidates().iter().all(|w| w.iter().filter(|&&ch| ch == Letter::E).count() == 2));
+
+    // the other way around, both E's are shown, so there are at least two
+    let mut guesser = Guesser::new(Vec::new());
+    let fb = crate::play::check_word(eerie, theme);
+    guesser.analyze(std::array::from_fn(|i| (theme[i], fb[i]))).unwrap();
+    assert_eq!(guesser.export_state().counts[0], LetterCount { letter: Letter::E, min: 2, max: 5 });
+    assert_eq!(guesser.explain_rejection(eerie), None);
+    // then a grey E claiming there's only one contradicts it
+    let ebide = word(b"EBIDE");
+    let fb = WordFeedback::new([LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Excluded, LetterFeedback::Confirmed]);
+    let chars = std::array::from_fn(|i| (ebide[i], fb[i]));
+    let before = guesser.export_state();
+    assert_eq!(guesser.analyze(chars), Err(Contradiction::CountConflict(Letter::E)));
+    assert_eq!(guesser.export_state(), before);
+  }
+
+  #[test]
+  fn test_skip() {
+    let mut guesser = Guesser::new(Vec::new());
+    let ranked = guesser.suggestions(4).to_vec();
+    assert_eq!(guesser.guess(), Some(&ranked[0]));
+    for expected in &ranked[1..] {
+      assert_eq!(guesser.skip(), Some(expected));
+      assert_eq!(guesser.guess(), Some(expected));
+      assert_eq!(guesser.suggestions(1), [*expected]);
+    }
+
+    let answer = word(b"SHAKE");
+    let guess = *guesser.guess().unwrap();
+    let fb = crate::play::check_word(answer, guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+    guesser.prune(1);
+    assert_eq!(guesser.guess(), guesser.candidates().first(), "skips only last for the turn");
+
+    let last = guesser.candidates().len() - 1;
+    for _ in 0..last {
+      assert!(guesser.skip().is_some());
+    }
+    assert_eq!(guesser.skip(), None);
+    assert_eq!(guesser.guess(), guesser.candidates().last());
+  }
+
+  #[test]
+  fn test_prior() {
+    // same letters in every position but the first, so they score the same
+    let words: &'static [Word] = Box::leak(Box::new([word(b"BATCH"), word(b"PATCH")]));
+    let mut guesser = Guesser::new_with_lists(words, words);
+    guesser.prune(1);
+    assert_eq!(guesser.candidates(), words);
+
+    let prior = Box::leak(Box::new([(word(b"BATCH"), 3), (word(b"PATCH"), 40)]));
+    guesser.set_prior(prior);
+    guesser.prune(1);
+    assert_eq!(guesser.candidates(), [word(b"PATCH"), word(b"BATCH")]);
+  }
+
+  #[test]
+  fn test_six_letters() {
+    static WORDS: [Word<6>; 6] = [
+      Word::from_bytes(*b"PLANET").unwrap(), Word::from_bytes(*b"PLANTS").unwrap(), Word::from_bytes(*b"PLACED").unwrap(),
+      Word::from_bytes(*b"PALLET").unwrap(), Word::from_bytes(*b"PLAINS").unwrap(), Word::from_bytes(*b"SPLINE").unwrap(),
+    ];
+    let answer = WORDS[0];
+    let mut guesser = Guesser::new_with_lists(&WORDS, &WORDS);
+    for turn in 1..=6 {
+      let guess = *guesser.guess().unwrap();
+      if guess == answer {
+        return;
+      }
+      let fb = crate::play::check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+      guesser.prune(turn);
+      assert!(guesser.candidates().contains(&answer), "{guesser}");
+    }
+    panic!("failed to solve PLANET");
+  }
+
+  #[test]
+  fn test_opener() {
+    let answer = word(b"SHAKE");
+    let opener = word(b"SALET");
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.set_opener(opener);
+    for _ in 0..2 {
+      assert_eq!(guesser.guess(), Some(&opener));
+      let fb = crate::play::check_word(answer, opener);
+      guesser.analyze(std::array::from_fn(|i| (opener[i], fb[i]))).unwrap();
+      guesser.prune(1);
+      assert_ne!(guesser.guess(), Some(&opener), "opener should only be used on turn one");
+      guesser.reset();
+    }
+  }
+
+  #[test]
+  fn test_information_gain() {
+    static WORDS: [Word; 4] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")];
+    let guesser = Guesser::new_with_lists(&WORDS, &WORDS);
+    // buckets of 1, 1, 2: -(1/4 log2 1/4)*2 - 1/2 log2 1/2
+    assert!((guesser.information_gain(word(b"BLAHS")) - 1.5).abs() < 1e-9);
+    assert!((guesser.information_gain(word(b"HUMBL")) - 2.0).abs() < 1e-9);
+    assert_eq!(guesser.information_gain(word(b"QUIZZ")), 0.0);
+  }
+
+  #[test]
+  fn test_best_openers() {
+    static ANSWERS: [Word; 4] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")];
+    static GUESSES: [Word; 6] = [word(b"QUIZZ"), word(b"BATCH"), word(b"BLAHS"), word(b"HATCH"), word(b"HUMBL"), word(b"MATCH")];
+    let mut guesser = Guesser::new_with_lists(&ANSWERS, &GUESSES);
+    let openers = guesser.best_openers(3);
+    assert_eq!(openers.iter().map(|&(w, _)| w).collect::<Vec<_>>(), [word(b"HUMBL"), word(b"BLAHS"), word(b"BATCH")]);
+    for (word, bits) in openers {
+      assert!((bits - guesser.information_gain(word)).abs() < 1e-9, "{word}");
+    }
+    let matrix = Box::leak(Box::new(FeedbackMatrix::new(&GUESSES, &ANSWERS, NonZeroUsize::MIN)));
+    let without = guesser.best_openers(GUESSES.len());
+    guesser.set_feedback_matrix(matrix);
+    assert_eq!(guesser.best_openers(GUESSES.len()), without);
+    assert_eq!(without.last(), Some(&(word(b"QUIZZ"), 0.0)));
+  }
+
+  #[test]
+  fn test_feedback_matrix() {
+    static ANSWERS: [Word; 4] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")];
+    static GUESSES: [Word; 6] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH"), word(b"BLAHS"), word(b"HUMBL")];
+    let matrix = Box::leak(Box::new(FeedbackMatrix::new(&GUESSES, &ANSWERS, NonZeroUsize::MIN)));
+    for (row, guess) in GUESSES.iter().enumerate() {
+      for answer in &ANSWERS {
+        assert_eq!(matrix.get(row, matrix.column(answer).unwrap()), crate::play::check_word(*answer, *guess));
+      }
+    }
+    assert_eq!(matrix.column(&word(b"BLAHS")), None);
+
+    let mut guesser = Guesser::new_with_lists(&ANSWERS, &GUESSES);
+    let computed = guesser.bucket_guesses();
+    guesser.set_feedback_matrix(matrix);
+    let cached = guesser.bucket_guesses();
+    assert_eq!(cached.len(), computed.len());
+    for ((a, x), (b, y)) in cached.iter().zip(computed.iter()) {
+      assert_eq!(a, b);
+      assert_eq!(x.entries().as_slice(), y.entries().as_slice());
+    }
+  }
+
+  #[test]
+  fn test_entropy() {
+    static WORDS: [Word; 4] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")];
+    static GUESSES: [Word; 6] = [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH"), word(b"BLAHS"), word(b"HUMBL")];
+    let mut guesser = Guesser::new_with_lists(&WORDS, &GUESSES);
+    let buckets = guesser.bucket_guesses();
+    let bits = buckets.iter().map(|(w, m)| (*w, entropy(m, WORDS.len()))).collect::<Vec<_>>();
+    // a candidate only tells apart itself from the rest
+    assert!((bits[0].1 - (0.25*4.0f64.log2() + 0.75*(4.0f64/3.0).log2())).abs() < 1e-9);
+    // BLAHS can't tell apart HATCH and MATCH
+    assert!((bits[4].1 - 1.5).abs() < 1e-9, "{bits:?}");
+    // HUMBL tells apart all four
+    assert!((bits[5].1 - 2.0).abs() < 1e-9, "{bits:?}");
+
+    let mut guesser = Guesser::new_with_lists(&WORDS, &GUESSES);
+    guesser.set_strategy(Strategy::Entropy);
+    guesser.prune(1);
+    assert_eq!(guesser.guess(), Some(&word(b"HUMBL")));
+    let mut guesser = Guesser::new_with_lists(&WORDS, &GUESSES);
+    guesser.set_strategy(Strategy::Entropy);
+    guesser.prune(6);
+    assert!(WORDS.contains(guesser.guess().unwrap()), "last turn must guess a candidate");
+  }
+
+  #[test]
+  fn test_minimax() {
+    static WORDS: [Word; 6] = [word(b"FIGHT"), word(b"LIGHT"), word(b"MIGHT"), word(b"NIGHT"), word(b"SIGHT"), word(b"TIGHT")];
+    static GUESSES: [Word; 9] = [
+      word(b"FIGHT"), word(b"LIGHT"), word(b"MIGHT"), word(b"NIGHT"), word(b"SIGHT"), word(b"TIGHT"),
+      word(b"FLAME"), word(b"SLANT"), word(b"FILMS"),
+    ];
+    let mut guesser = Guesser::new_with_lists(&WORDS, &GUESSES);
+    guesser.set_strategy(Strategy::Minimax);
+    guesser.prune(1);
+    let chosen = *guesser.guess().unwrap();
+
+    let worst_case = |guess: Word| {
+      let mut feedback = WORDS.map(|w| crate::play::check_word(w, guess));
+      feedback.sort();
+      feedback.chunk_by(|a, b| a == b).map(<[_]>::len).max().unwrap()
+    };
+    let best = GUESSES.iter().map(|&g| worst_case(g)).min().unwrap();
+    assert_eq!(worst_case(chosen), best, "{chosen} is not a minimax guess");
+    assert_eq!(best, 2);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn test_serde() {
+    use LetterFeedback::*;
+    for (fb, tag) in [(Excluded, "\"grey\""), (Required, "\"yellow\""), (Confirmed, "\"green\"")] {
+      assert_eq!(serde_json::to_string(&fb).unwrap(), tag);
+      assert_eq!(serde_json::from_str::<LetterFeedback>(tag).unwrap(), fb);
+    }
+    assert!(serde_json::from_str::<LetterFeedback>("\"blue\"").is_err());
+
+    let fb = WordFeedback::new([Confirmed, Excluded, Required, Excluded, Confirmed]);
+    let json = serde_json::to_string(&fb).unwrap();
+    assert_eq!(json, r#"["green","grey","yellow","grey","green"]"#);
+    assert_eq!(serde_json::from_str::<WordFeedback>(&json).unwrap(), fb);
+    assert!(serde_json::from_str::<WordFeedback>(r#"["green","grey"]"#).is_err());
+
+    let state = GuesserState {
+      excluded: vec![Letter::C, Letter::N],
+      required: vec![RequiredLetter { letter: Letter::A, not_at: vec![2] }],
+      confirmed: [None, None, None, None, Some(Letter::E)],
+      counts: vec![LetterCount { letter: Letter::E, min: 1, max: 1 }],
+    };
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, r#"{"excluded":["C","N"],"required":[{"letter":"A","not_at":[2]}],"confirmed":[null,null,null,null,"E"],"counts":[{"letter":"E","min":1,"max":1}]}"#);
+    assert_eq!(serde_json::from_str::<GuesserState>(&json).unwrap(), state);
+    let old = r#"{"excluded":[],"required":[],"confirmed":[null,null,null,null,null]}"#;
+    assert_eq!(serde_json::from_str::<GuesserState>(old).unwrap(), GuesserState::default());
   }
 }
@@ -0,0 +1,24 @@
+#![feature(impl_trait_in_fn_trait_return)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+#![cfg_attr(test, feature(test))]
+
+pub mod word;
+pub mod dictionary;
+pub mod guess;
+pub mod play;
+pub mod stats;
+
+pub use word::{Letter, Word};
+pub use guess::{Guesser, LetterFeedback, WordFeedback};
+pub use play::check_word;
+
+/// Print when `$guesser` was told to show its "thought process"
+macro_rules! verbose_println {
+  ($guesser:expr, $($arg:tt)*) => {
+    if $guesser.is_verbose {
+      println!($($arg)*);
+    }
+  };
+}
+
+pub(crate) use verbose_println;
@@ -1,14 +1,7 @@
-#![feature(test, impl_trait_in_fn_trait_return)]
+#![cfg_attr(test, feature(test))]
 
-use std::{io::stdin, num::NonZeroUsize, sync::OnceLock};
-use arrayvec::ArrayVec;
-use guess::*;
-use crate::{dictionary::FIVE_LETTER_WORDS, play::check_word, word::{Letter, Word}};
-
-mod word;
-mod dictionary;
-mod guess;
-mod play;
+use std::{collections::HashMap, io::{stdin, BufRead}, num::NonZeroUsize, path::PathBuf, sync::OnceLock, time::{Duration, Instant}};
+use wordle_helper::{dictionary::{self, Language, Ranking, ANSWERS}, guess::*, play::{check_word, Absurdle}, stats::*, word::{Letter, Word}};
 
 #[derive(Debug)]
 pub enum RunMode {
@@ -20,7 +13,35 @@ pub enum RunMode {
   Stats(NonZeroUsize),
 
   /// Provide the winning word and see how the application tries to solve it
-  Auto(Word),
+  ///
+  /// Kept as typed until the word length is known.
+  Auto(String),
+
+  /// Solve every word in a list, reporting only whether each was won and in how many turns
+  AutoFile(PathBuf),
+
+  /// Print every board in a log written with `--log`
+  Replay(PathBuf),
+
+  /// Print how often each letter appears at each position among the answers
+  Freq,
+
+  /// Play against a host that picks the feedback keeping the most answers possible, instead of a fixed word
+  Absurdle,
+
+  /// Guess a hidden answer picked at random, reproducibly with `--seed`, like playing Wordle offline
+  ///
+  /// Only the words are typed in, their feedback comes from the hidden answer. Nothing is suggested.
+  Practice,
+
+  /// Practice against the answer picked for today's date, the same for everyone with the same answer list
+  Daily,
+
+  /// Print the 20 first guesses that reveal the most about the answers
+  ///
+  /// Grades every allowed guess against every answer, so it's slow for the full dictionary.
+  /// With `--cache`, the grades come from the feedback matrix instead.
+  FindOpener,
 }
 
 #[derive(Debug)]
@@ -31,6 +52,85 @@ pub struct AppOptions {
   /// Every confirmed letter MUST be used in all subsequent guesses
   pub is_hardmode: bool,
 
+  /// Print words in lowercase, like the board
+  pub is_lowercase: bool,
+
+  /// Only narrow down the candidates with the feedback given, never suggesting anything
+  pub is_assist: bool,
+
+  /// Print how long the solver took each turn, not counting waiting on input, and in total
+  pub is_timing: bool,
+
+  /// Print how many candidates are left after each turn, like assist mode does
+  pub show_counts: bool,
+
+  /// How feedback is typed in and shown
+  pub symbols: FeedbackSymbols,
+
+  /// Glyphs for the stats histogram, and for feedback unless other output symbols were given
+  pub palette: Palette,
+
+  /// Number of guesses allowed before the game is lost
+  ///
+  /// Defaults to one more than the number of `boards`, plus five.
+  pub max_turns: u32,
+
+  /// How many words auto mode solves at once with the same guesses, like Dordle or Quordle
+  pub boards: usize,
+
+  /// Letters per word, from 4 to 7
+  ///
+  /// Only five-letter words have an embedded list, other lengths need `dictionary`.
+  pub length: usize,
+
+  /// How each suggestion is chosen
+  pub strategy: Strategy,
+
+  /// How candidates are ordered by letter frequency
+  pub ranking: Ranking,
+
+  /// Append each finished interactive or auto game to this file
+  pub log: Option<PathBuf>,
+
+  /// Always open with this word, instead of the embedded list's [`Language::opener`]
+  pub opener: Option<Word>,
+
+  /// Grade every guess against every answer once up front, instead of every turn
+  pub cache_feedback: bool,
+
+  /// Games played between each progress update of a stats run
+  pub batch_size: NonZeroUsize,
+
+  /// Skip the games a stats run already saved to [`CHECKPOINT_FILE`] before it was interrupted
+  pub resume: bool,
+
+  /// Burners may be suggested to tell candidates apart, turned off with `--no-tiebreak`
+  pub is_tiebreak: bool,
+
+  /// Most candidates printed after each turn, or all of them with `--show-all`
+  pub candidate_limit: Option<usize>,
+
+  /// Limit how many threads grade guesses, defaults to all available
+  pub threads: Option<NonZeroUsize>,
+
+  /// Shuffle the answers of a stats run with this seed, so it can be reproduced
+  pub seed: Option<u64>,
+
+  /// File format for the results of a stats run
+  pub format: StatsFormat,
+
+  /// Which embedded word list to play with
+  pub language: Language,
+
+  /// Word list to use instead of the embedded one
+  pub dictionary: Option<PathBuf>,
+
+  /// Word list to draw solutions from, if different from the allowed guesses
+  pub answers: Option<PathBuf>,
+
+  /// How often each word is used, to prefer common words among the last few candidates
+  pub prior: Option<PathBuf>,
+
   pub run_mode: RunMode,
 }
 
@@ -39,7 +139,7 @@ pub static OPTIONS: OnceLock<AppOptions> = OnceLock::new();
 #[allow(unused_macros)]
 macro_rules! verbose_print {
   ($($arg:tt)*) => {
-    if $crate::OPTIONS.get().unwrap().is_verbose {
+    if $crate::OPTIONS.get().is_some_and(|options| options.is_verbose) {
       print!($($arg)*);
     }
   };
@@ -48,12 +148,12 @@ macro_rules! verbose_print {
 #[allow(unused_macros)]
 macro_rules! verbose_println {
   () => {
-    if $crate::OPTIONS.get().unwrap().is_verbose {
+    if $crate::OPTIONS.get().is_some_and(|options| options.is_verbose) {
       println!();
     }
   };
   ($($arg:tt)*) => {
-    if $crate::OPTIONS.get().unwrap().is_verbose {
+    if $crate::OPTIONS.get().is_some_and(|options| options.is_verbose) {
       println!($($arg)*);
     }
   };
@@ -62,32 +162,478 @@ macro_rules! verbose_println {
 #[allow(unused_imports)]
 pub(crate) use {verbose_print, verbose_println};
 
-pub struct Attempts(ArrayVec::<WordFeedback, 6>);
+/// Three symbols for green, yellow and grey, in that order, as [`FeedbackSymbols`] stores them
+fn parse_symbols(s: &str) -> Option<[char; 3]> {
+  let [green, yellow, grey] = s.chars().collect::<Vec<_>>().try_into().ok()?;
+  Some([grey, yellow, green])
+}
+
+/// Display `word` in the case chosen with `--lowercase`
+fn shown<const N: usize>(word: Word<N>) -> impl std::fmt::Display {
+  std::fmt::from_fn(move |f| {
+    if OPTIONS.get().is_some_and(|options| options.is_lowercase) {
+      write!(f, "{}", word.display_lower())
+    } else {
+      write!(f, "{word}")
+    }
+  })
+}
+
+/// `--turns 0` or `--unlimited`: keep guessing until the word is found or nothing is left to guess
+pub const UNLIMITED_TURNS: u32 = u32::MAX;
+
+/// Room for every turn of a game, without reserving [`UNLIMITED_TURNS`] of them up front
+const fn turn_capacity(max_turns: u32) -> usize {
+  if max_turns == UNLIMITED_TURNS { 6 } else { max_turns as usize }
+}
+
+pub struct Attempts<const N: usize = 5> {
+  rows: Vec<WordFeedback<N>>,
+  max_turns: u32,
+  symbols: FeedbackSymbols,
+}
 
-impl Attempts {
+impl<const N: usize> Attempts<N> {
   pub const fn new() -> Self {
-    Self(ArrayVec::new_const())
+    Self { rows: Vec::new(), max_turns: 6, symbols: FeedbackSymbols::DEFAULT }
+  }
+
+  pub fn with_capacity(max_turns: u32) -> Self {
+    Self { rows: Vec::with_capacity(turn_capacity(max_turns)), max_turns, symbols: FeedbackSymbols::DEFAULT }
+  }
+
+  /// Show the rows with `symbols` instead of [`FeedbackSymbols::DEFAULT`]
+  pub const fn set_symbols(&mut self, symbols: FeedbackSymbols) {
+    self.symbols = symbols;
+  }
+
+  pub fn push(&mut self, stats: WordFeedback<N>) {
+    self.rows.push(stats);
   }
 
-  pub fn push(&mut self, stats: WordFeedback) {
-    self.0.push(stats);
+  pub fn pop(&mut self) -> Option<WordFeedback<N>> {
+    self.rows.pop()
+  }
+
+  /// Each turn's feedback, first turn first
+  pub fn iter(&self) -> std::slice::Iter<'_, WordFeedback<N>> {
+    self.rows.iter()
+  }
+
+  /// The "Wordle 123 4/6" header followed by the grid, ready to post
+  ///
+  /// Shows `X` instead of the number of turns if the last row isn't all green.
+  /// There's no "/6" when turns are unlimited.
+  pub fn share_text(&self, puzzle_id: Option<u32>) -> String {
+    let is_won = self.rows.last() == Some(&WordFeedback::new([LetterFeedback::Confirmed; N]));
+    let mut score = if is_won { self.rows.len().to_string() } else { "X".to_string() };
+    if self.max_turns != UNLIMITED_TURNS {
+      score += &format!("/{}", self.max_turns);
+    }
+    match puzzle_id {
+      Some(id) => format!("Wordle {id} {score}\n\n{self}"),
+      None => format!("Wordle {score}\n\n{self}"),
+    }
   }
 }
 
-impl std::fmt::Display for Attempts {
+impl<'a, const N: usize> IntoIterator for &'a Attempts<N> {
+  type Item = &'a WordFeedback<N>;
+  type IntoIter = std::slice::Iter<'a, WordFeedback<N>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
+
+impl<const N: usize> Default for Attempts<N> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<const N: usize> std::fmt::Display for Attempts<N> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    for row in 0..self.0.len() {
-      for col in &*self.0[row] {
-        col.fmt(f)?;
-      }
-      if row + 1 < self.0.len() {
+    for (row, &stats) in self.iter().enumerate() {
+      if row > 0 {
         '\n'.fmt(f)?;
       }
+      self.symbols.display(stats).fmt(f)?;
     }
     Ok(())
   }
 }
 
+fn apply_options(guesser: &mut Guesser, options: &AppOptions) {
+  apply_strategy_options(guesser, options);
+  // the embedded lists come with their best opener worked out
+  let is_embedded = options.dictionary.is_none() && options.answers.is_none();
+  if let Some(opener) = options.opener.or(is_embedded.then(|| options.language.opener())) {
+    guesser.set_opener(opener);
+  }
+  if options.cache_feedback {
+    guesser.set_feedback_matrix(dictionary::feedback_matrix());
+  }
+  if let Some(prior) = dictionary::prior() {
+    guesser.set_prior(prior);
+  }
+}
+
+/// The options that work for any word length
+fn apply_strategy_options<const N: usize>(guesser: &mut Guesser<N>, options: &AppOptions) {
+  guesser.set_hardmode(options.is_hardmode);
+  guesser.set_strategy(options.strategy);
+  guesser.set_ranking(options.ranking);
+  if options.is_assist {
+    // nothing gets suggested, so don't spend any time choosing
+    guesser.set_strategy(Strategy::Frequency);
+    guesser.set_tiebreak_window(std::ops::RangeInclusive::new(1, 0));
+  }
+  guesser.set_max_turns(options.max_turns);
+  guesser.set_tiebreak(options.is_tiebreak);
+  guesser.set_verbose(options.is_verbose);
+  if let Some(threads) = options.threads {
+    guesser.set_threads(threads);
+  }
+}
+
+/// A line of interactive input
+#[derive(Debug, PartialEq, Eq)]
+pub enum Entry<const N: usize = 5> {
+  /// A word that was played and the feedback it got
+  Played([(Letter, LetterFeedback); N]),
+  /// `why not WORD?`: ask why a word isn't a candidate
+  WhyNot(Word<N>),
+  /// `undo`: take back the last feedback
+  Undo,
+  /// `skip`: ask for the next best suggestion instead
+  Skip,
+  /// `grade GUESS TARGET`: show the feedback `GUESS` would get if the answer were `TARGET`
+  Grade(Word<N>, Word<N>),
+  /// `list`: print every remaining candidate again
+  List,
+  /// `count`: print how many candidates remain
+  Count,
+  /// `anagram [LETTERS]`: list the allowed words containing every one of `LETTERS`, or of the letters known so far
+  Anagram(Option<Vec<Letter>>),
+  /// `match PATTERN`: list the candidates that fit a pattern like `?R?_E`
+  Match(String),
+}
+
+/// Read the word that was played and the feedback it got, on one line or one per line, or a command instead of the word
+///
+/// Returns [`None`] at the end of input, even partway through an entry, or if the word is "exit"
+pub fn read_entry<const N: usize>(input: &mut impl BufRead, buf: &mut String, symbols: &FeedbackSymbols) -> Result<Option<Entry<N>>, String> {
+  let mut next_line = |buf: &mut String| -> Result<bool, String> {
+    buf.clear();
+    let n = input.read_line(buf).map_err(|e| e.to_string())?;
+    buf.truncate(buf.trim_end().len());
+    Ok(n > 0)
+  };
+  if !next_line(buf)? || buf == "exit" {
+    return Ok(None);
+  }
+  if let Some(command) = parse_command(buf) {
+    return command.map(Some);
+  }
+  if buf.contains(char::is_whitespace) {
+    return parse_played(buf, symbols).map(|played| Some(Entry::Played(played)));
+  }
+  let word = buf.parse::<Word<N>>().map_err(|e| format!("invalid word `{buf}`: {e} ({})", expected_entry::<N>()))?;
+  if !next_line(buf)? {
+    return Ok(None);
+  }
+  parse_played(&format!("{word} {buf}"), symbols).map(|played| Some(Entry::Played(played)))
+}
+
+/// Parse any input other than a played word, or [`None`] if `line` isn't a command
+fn parse_command<const N: usize>(line: &str) -> Option<Result<Entry<N>, String>> {
+  match line {
+    "undo" => return Some(Ok(Entry::Undo)),
+    "skip" => return Some(Ok(Entry::Skip)),
+    "list" => return Some(Ok(Entry::List)),
+    "count" => return Some(Ok(Entry::Count)),
+    "anagram" => return Some(Ok(Entry::Anagram(None))),
+    _ => {}
+  }
+  if let Some(pattern) = line.strip_prefix("match ") {
+    return Some(Ok(Entry::Match(pattern.trim().to_string())));
+  }
+  if let Some(letters) = line.strip_prefix("anagram ") {
+    return Some(letters.trim().bytes()
+      .map(|b| Letter::from_u8(b.to_ascii_uppercase()).ok_or_else(|| format!("invalid letter `{}` in `{letters}`", b as char)))
+      .collect::<Result<Vec<_>, _>>()
+      .map(|letters| Entry::Anagram(Some(letters))));
+  }
+  if let Some(word) = line.strip_prefix("why not ") {
+    let word = word.trim_end_matches('?').trim();
+    return Some(word.parse::<Word<N>>()
+      .map(Entry::WhyNot)
+      .map_err(|e| format!("invalid word `{word}`: {e}")));
+  }
+  let words = line.strip_prefix("grade ")?;
+  let parse = |word: &str| word.parse::<Word<N>>().map_err(|e| format!("invalid word `{word}`: {e}"));
+  Some(match words.split_whitespace().collect::<Vec<_>>()[..] {
+    [guess, target] => parse(guess).and_then(|guess| Ok(Entry::Grade(guess, parse(target)?))),
+    _ => Err(format!("expected `grade GUESS TARGET`, found `{line}`")),
+  })
+}
+
+/// Read a word to grade against the hidden `answer` of a practice game, or a command instead of the word
+///
+/// Returns [`None`] at the end of input, or if the word is "exit"
+pub fn read_practice_entry<const N: usize>(input: &mut impl BufRead, buf: &mut String, answer: Word<N>) -> Result<Option<Entry<N>>, String> {
+  buf.clear();
+  let n = input.read_line(buf).map_err(|e| e.to_string())?;
+  buf.truncate(buf.trim_end().len());
+  if n == 0 || buf == "exit" {
+    return Ok(None);
+  }
+  if let Some(command) = parse_command(buf) {
+    return command.map(Some);
+  }
+  let word = buf.trim_start().parse::<Word<N>>().map_err(|e| format!("invalid word `{buf}`: {e} (expected a {N}-letter guess)"))?;
+  let stats = check_word(answer, word);
+  Ok(Some(Entry::Played(std::array::from_fn(|i| (word[i], stats[i])))))
+}
+
+/// A hidden answer for practice mode, the same one for the same `seed`
+fn pick_practice_answer<const N: usize>(answers: &[Word<N>], seed: Option<u64>) -> Option<Word<N>> {
+  use rand::{SeedableRng, seq::IndexedRandom};
+  let seed = seed.unwrap_or_else(|| std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |t| t.as_nanos() as u64));
+  answers.choose(&mut rand::rngs::StdRng::seed_from_u64(seed)).copied()
+}
+
+/// Days from 1970-01-01 to the first Wordle puzzle, 2021-06-19
+const FIRST_DAILY_PUZZLE: u64 = 18797;
+
+fn unix_time() -> u64 {
+  std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |t| t.as_secs())
+}
+
+/// Which daily puzzle it is at `unix_time` seconds, counting days in UTC from the first Wordle puzzle as #0
+fn daily_puzzle(unix_time: u64) -> u64 {
+  (unix_time / (24*60*60)).saturating_sub(FIRST_DAILY_PUZZLE)
+}
+
+/// The hidden answer for daily puzzle `puzzle`, the same for everyone with the same answer list
+///
+/// Picks from `answers` in alphabetical order, which is file order for the embedded lists, so neither
+/// `--rank`, `--opener` nor how the list was sorted changes it. The index is `puzzle` mixed by SplitMix64,
+/// written out here so it can't change with a dependency.
+fn pick_daily_answer<const N: usize>(answers: &[Word<N>], puzzle: u64) -> Option<Word<N>> {
+  let mut sorted = answers.to_vec();
+  sorted.sort_unstable();
+  let mut z = puzzle.wrapping_add(0x9E37_79B9_7F4A_7C15);
+  z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  z ^= z >> 31;
+  sorted.get((z % sorted.len().max(1) as u64) as usize).copied()
+}
+
+fn expected_entry<const N: usize>() -> String {
+  format!("expected a {N}-letter guess then {N} feedback symbols")
+}
+
+/// Parse a word and its feedback typed with `symbols` separated by whitespace, like `CRANE +?__+`
+pub fn parse_played<const N: usize>(line: &str, symbols: &FeedbackSymbols) -> Result<[(Letter, LetterFeedback); N], String> {
+  let (word, feedback) = line.trim().split_once(char::is_whitespace)
+    .ok_or_else(|| format!("missing feedback in `{line}` ({})", expected_entry::<N>()))?;
+  let word = word.parse::<Word<N>>()
+    .map_err(|e| format!("invalid word `{word}`: {e} ({})", expected_entry::<N>()))?;
+  let feedback = feedback.trim_start();
+  let stats = symbols.parse::<N>(feedback).map_err(|e| {
+    let [grey, yellow, green] = symbols.input;
+    match e {
+      FeedbackParseError::UnknownSymbol(_) if symbols.input != FeedbackSymbols::DEFAULT.input =>
+        format!("invalid feedback `{feedback}`: {e}, or `{green}{yellow}{grey}` ({})", expected_entry::<N>()),
+      _ => format!("invalid feedback `{feedback}`: {e} ({})", expected_entry::<N>()),
+    }
+  })?;
+  Ok(std::array::from_fn(|i| (word[i], stats[i])))
+}
+
+/// Count of each letter at each position and overall, most common letters first
+pub fn frequency_table(words: &[Word]) -> String {
+  let freq = dictionary::positional_frequencies(words);
+  let mut letters: Vec<(Letter, u32)> = Letter::iter()
+    .map(|ch| (ch, freq.iter().map(|f| f[ch.index()]).sum()))
+    .collect();
+  letters.sort_by_key(|&(_, total)| std::cmp::Reverse(total));
+  let mut table = format!("letter {:>6} {:>6} {:>6} {:>6} {:>6} {:>6}\n", 1, 2, 3, 4, 5, "total");
+  for (ch, total) in letters {
+    let i = ch.index();
+    table += &format!("{:<6} {:>6} {:>6} {:>6} {:>6} {:>6} {:>6}\n", ch, freq[0][i], freq[1][i], freq[2][i], freq[3][i], freq[4][i], total);
+  }
+  table
+}
+
+/// Play a whole game against `answer` without printing anything
+///
+/// The game is lost if the guesser runs out of words before finding `answer`.
+pub fn play_game(guesser: &mut Guesser, answer: Word, max_turns: u32) -> GameRecord {
+  guesser.reset();
+  let mut guesses = Vec::with_capacity(turn_capacity(max_turns));
+  let mut remaining = Vec::with_capacity(turn_capacity(max_turns));
+  let mut burners = 0;
+  for turn in 1..=max_turns {
+    let Some(&guess) = guesser.guess() else { break };
+    guesses.push(guess);
+    let is_burner = guesser.burner().is_some();
+    burners += u32::from(is_burner);
+    remaining.push(guesser.candidates().len() - usize::from(is_burner));
+    if guess == answer {
+      return GameRecord { word: answer, success: true, guesses, burners, remaining };
+    }
+    let stats = check_word(answer, guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i])))
+      .expect("graded feedback should never be contradictory");
+    guesser.prune(turn);
+  }
+  GameRecord { word: answer, success: false, guesses, burners, remaining }
+}
+
+/// Solve several boards at once with the same guesses, like Dordle or Quordle
+///
+/// Each guess is chosen to be good across every unsolved board, see [`joint_guess`].
+/// Returns the guesses and the turn each board was solved on, if it was.
+pub fn play_boards(guessers: &mut [Guesser], answers: &[Word], max_turns: u32) -> (Vec<Word>, Vec<Option<u32>>) {
+  let mut solved = vec![None; answers.len()];
+  let mut guesses = Vec::with_capacity(turn_capacity(max_turns));
+  for guesser in guessers.iter_mut() {
+    guesser.reset();
+  }
+  for turn in 1..=max_turns {
+    let active: Vec<&Guesser> = guessers.iter().zip(&solved)
+      .filter_map(|(guesser, solved_on)| solved_on.is_none().then_some(guesser))
+      .collect();
+    let Some(guess) = joint_guess(&active, max_turns - turn + 1) else { break };
+    guesses.push(guess);
+    for ((guesser, &answer), solved_on) in guessers.iter_mut().zip(answers).zip(&mut solved) {
+      if solved_on.is_some() {
+        continue;
+      }
+      if guess == answer {
+        *solved_on = Some(turn);
+        continue;
+      }
+      let stats = check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i])))
+        .expect("graded feedback should never be contradictory");
+      guesser.prune(turn);
+    }
+    if solved.iter().all(Option::is_some) {
+      break;
+    }
+  }
+  (guesses, solved)
+}
+
+/// The guess that reveals the most information summed across `boards`
+///
+/// A board down to its last candidate is solved for free, so that candidate is played first,
+/// and the last unsolved board plays just like a single game.
+/// Once there are only enough turns left to guess once per board, the closest board gets its own suggestion.
+/// Otherwise only each board's top few suggestions are considered.
+pub fn joint_guess(boards: &[&Guesser], turns_left: u32) -> Option<Word> {
+  if let [board] = boards {
+    return board.guess().copied();
+  }
+  if let Some(board) = boards.iter().find(|board| board.candidates().len() == 1) {
+    return Some(board.candidates()[0]);
+  }
+  if turns_left as usize <= boards.len() {
+    return boards.iter().min_by_key(|board| board.candidates().len())?.guess().copied();
+  }
+  let mut pool = Vec::new();
+  for &word in boards.iter().flat_map(|board| board.suggestions(3)) {
+    if !pool.contains(&word) {
+      pool.push(word);
+    }
+  }
+  pool.into_iter()
+    .map(|word| (word, boards.iter().map(|board| board.information_gain(word)).sum::<f64>()))
+    // the first of the best, so ties go to the earlier board
+    .reduce(|best, next| if next.1 > best.1 { next } else { best })
+    .map(|(word, _)| word)
+}
+
+/// Games played between each progress update and checkpoint of a stats run, unless `--batch` says otherwise
+pub const DEFAULT_BATCH_SIZE: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// The first line of a stats checkpoint, so games are only resumed under the options they were played with
+fn checkpoint_header(options: &AppOptions, max_turns: u32) -> String {
+  let is_embedded = options.dictionary.is_none() && options.answers.is_none();
+  let opener = options.opener.or(is_embedded.then(|| options.language.opener()));
+  let path = |path: &Option<PathBuf>| path.as_ref().map_or("-".to_string(), |path| path.display().to_string());
+  format!(
+    "# turns={max_turns} strategy={:?} hardmode={} ranking={:?} opener={} tiebreak={} lang={:?} dict={} answers={} prior={}",
+    options.strategy, options.is_hardmode, options.ranking, opener.map_or("-".to_string(), |word| word.to_string()), options.is_tiebreak,
+    options.language, path(&options.dictionary), path(&options.answers), path(&options.prior),
+  )
+}
+
+/// Play a game against each of `answers`, appending each batch of `batch_size` finished games to `checkpoint`
+///
+/// With `resume`, games already in `checkpoint` aren't played again, unless they were played with different options.
+/// The games come back in the same order as `answers` either way.
+fn play_stats(answers: &[Word], max_turns: u32, batch_size: NonZeroUsize, checkpoint: Option<&std::path::Path>, resume: bool) -> std::io::Result<Vec<GameRecord>> {
+  use std::io::Write;
+  let header = checkpoint_header(OPTIONS.get().unwrap(), max_turns);
+  let mut finished: HashMap<Word, GameRecord> = HashMap::with_capacity(answers.len());
+  if let Some(path) = checkpoint && resume {
+    match std::fs::read_to_string(path) {
+      Ok(text) => match text.split_once('\n') {
+        Some((saved, games)) if saved == header => {
+          let games = read_checkpoint(games).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+          finished.extend(games.into_iter().map(|game| (game.word, game)));
+        }
+        _ => eprintln!("{} was saved with different options, starting over", path.display()),
+      },
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+      Err(e) => return Err(e),
+    }
+  }
+  let mut file = checkpoint.map(std::fs::File::create).transpose()?;
+  if let Some(file) = &mut file {
+    writeln!(file, "{header}")?;
+    // rewritten rather than appended to, in case the last line was cut off
+    for game in finished.values() {
+      writeln!(file, "{game}")?;
+    }
+  }
+  let remaining: Vec<Word> = answers.iter().copied().filter(|word| !finished.contains_key(word)).collect();
+  let new_guesser = || {
+    let mut guesser = Guesser::new(Vec::new());
+    apply_options(&mut guesser, OPTIONS.get().unwrap());
+    guesser
+  };
+  #[cfg(not(feature = "parallel"))]
+  let mut guesser = new_guesser();
+  // never more than `answers.len()`, so a batch bigger than the list still ends at 100%
+  let mut done = answers.len() - remaining.len();
+  for batch in remaining.chunks(batch_size.get()) {
+    // every game is independent, so each thread gets its own guesser
+    #[cfg(feature = "parallel")]
+    let games: Vec<GameRecord> = {
+      use rayon::prelude::*;
+      batch.par_iter().map_init(new_guesser, |guesser, &word| play_game(guesser, word, max_turns)).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let games: Vec<GameRecord> = batch.iter().map(|&word| play_game(&mut guesser, word, max_turns)).collect();
+    if let Some(file) = &mut file {
+      for game in &games {
+        writeln!(file, "{game}")?;
+      }
+      file.flush()?;
+    }
+    finished.extend(games.into_iter().map(|game| (game.word, game)));
+    done += batch.len();
+    println!("{:3.3}% complete", 100.0*done as f64/answers.len() as f64);
+  }
+  Ok(answers.iter().filter_map(|word| finished.remove(word)).collect())
+}
+
 fn main() {
   OPTIONS.set({
     use lexopt::prelude::*;
@@ -95,6 +641,32 @@ fn main() {
 
     let mut is_verbose = false;
     let mut is_hardmode = false;
+    let mut is_lowercase = false;
+    let mut is_assist = false;
+    let mut is_timing = false;
+    let mut show_counts = false;
+    let mut symbols = FeedbackSymbols::default();
+    let mut output_symbols = None;
+    let mut palette = Palette::default();
+    let mut max_turns = None;
+    let mut boards = 1;
+    let mut length = 5;
+    let mut strategy = Strategy::default();
+    let mut ranking = Ranking::default();
+    let mut format = StatsFormat::default();
+    let mut seed = None;
+    let mut threads = None;
+    let mut opener = None;
+    let mut log = None;
+    let mut cache_feedback = false;
+    let mut resume = false;
+    let mut is_tiebreak = true;
+    let mut candidate_limit = Some(DEFAULT_CANDIDATE_LIMIT);
+    let mut batch_size = DEFAULT_BATCH_SIZE;
+    let mut language = Language::default();
+    let mut dictionary = None;
+    let mut answers = None;
+    let mut prior = None;
     let mut run_mode = RunMode::Interactive;
 
     while let Some(arg) = parser.next().unwrap() {
@@ -103,38 +675,183 @@ fn main() {
 
         Short('h') | Long("hard") => is_hardmode = true,
 
+        Long("lowercase") => is_lowercase = true,
+
+        Long("assist") => is_assist = true,
+
+        Long("timing") => is_timing = true,
+
+        Long("show-counts") => show_counts = true,
+
+        Long("input-symbols") => {
+          symbols.input = parse_symbols(&parser.value().expect("`input-symbols` argument must have symbols")
+            .string().expect("symbols must be valid unicode"))
+            .expect("`input-symbols` must be three symbols, for green, yellow and grey");
+        }
+
+        Long("output-symbols") => {
+          output_symbols = Some(parse_symbols(&parser.value().expect("`output-symbols` argument must have symbols")
+            .string().expect("symbols must be valid unicode"))
+            .expect("`output-symbols` must be three symbols, for green, yellow and grey"));
+        }
+
+        Long("ascii") => palette = Palette::Ascii,
+
+        Long("palette") => {
+          palette = parser.value().expect("`palette` argument must name a palette")
+            .string().expect("palette must be valid unicode")
+            .parse().unwrap_or_else(|e| panic!("{e}"));
+        }
+
+        Short('t') | Long("turns") => {
+          let turns = parser.value().expect("`turns` argument must have a number of turns")
+            .parse().expect("failed to parse number argument");
+          max_turns = Some(if turns == 0 { UNLIMITED_TURNS } else { turns });
+        }
+
+        Long("unlimited") => max_turns = Some(UNLIMITED_TURNS),
+
+        Long("boards") => {
+          boards = parser.value().expect("`boards` argument must have a number of boards")
+            .parse().expect("failed to parse number argument");
+          assert!(boards > 0, "must play at least one board");
+        }
+
+        Long("length") => {
+          length = parser.value().expect("`length` argument must have a number of letters")
+            .parse().expect("failed to parse number argument");
+          assert!((4..=7).contains(&length), "words must have between 4 and 7 letters");
+        }
+
+        Long("strategy") => {
+          strategy = parser.value().expect("`strategy` argument must name a strategy")
+            .string().expect("strategy must be valid unicode")
+            .parse().unwrap_or_else(|e| panic!("{e}"));
+        }
+
+        Long("rank") => {
+          ranking = parser.value().expect("`rank` argument must name a ranking")
+            .string().expect("ranking must be valid unicode")
+            .parse().unwrap_or_else(|e| panic!("{e}"));
+        }
+
+        Long("log") => log = Some(PathBuf::from(parser.value().expect("`log` argument must have a path to a log file"))),
+
+        Long("replay") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::Replay(PathBuf::from(parser.value().expect("`replay` argument must have a path to a log file")));
+        }
+
+        Long("opener") => {
+          opener = Some(parser.value().expect("`opener` argument must have a word")
+            .string().expect("`opener` word must be valid unicode")
+            .parse().unwrap_or_else(|e| panic!("`opener` word must be five ASCII letters: {e}")));
+        }
+
+        Long("cache") => cache_feedback = true,
+
+        Long("resume") => resume = true,
+
+        Long("no-tiebreak") => is_tiebreak = false,
+
+        Long("show-all") => candidate_limit = None,
+
+        Long("candidate-limit") => {
+          candidate_limit = Some(parser.value().expect("`candidate-limit` argument must have a number of words")
+            .parse().expect("`candidate-limit` must be a number"));
+        }
+
+        Long("batch") => {
+          batch_size = parser.value().expect("`batch` argument must have a number of games")
+            .parse().expect("`batch` must be a positive number");
+        }
+
+        Long("threads") => {
+          threads = Some(parser.value().expect("`threads` argument must have a number of threads")
+            .parse().expect("failed to parse number argument"));
+        }
+
+        Long("seed") => {
+          seed = Some(parser.value().expect("`seed` argument must have a number")
+            .parse().expect("failed to parse number argument"));
+        }
+
+        Long("format") => {
+          format = parser.value().expect("`format` argument must name a file format")
+            .string().expect("format must be valid unicode")
+            .parse().unwrap_or_else(|e| panic!("{e}"));
+        }
+
+        Long("lang") => {
+          language = parser.value().expect("`lang` argument must name a language")
+            .string().expect("language must be valid unicode")
+            .parse().unwrap_or_else(|e| panic!("{e}"));
+        }
+
+        Long("dict") => dictionary = Some(PathBuf::from(parser.value().expect("`dict` argument must have a path to a word list"))),
+
+        Long("answers") => answers = Some(PathBuf::from(parser.value().expect("`answers` argument must have a path to a word list"))),
+
         Short('s') | Long("stats") => {
           assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
           run_mode = RunMode::Stats(parser.optional_value().map_or(
-            const { unsafe { NonZeroUsize::new_unchecked(usize::MAX) } },
+            const { NonZeroUsize::new(usize::MAX).unwrap() },
             |s| s.parse().expect("failed to parse number argument"),
           ));
         }
 
         Short('a') | Long("auto") => {
           assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
-          let s = parser.value().expect("`auto` argument must have a word to solve for");
-          let &[
-            c1 @ (b'A'..=b'Z' | b'a'..=b'z'),
-            c2 @ (b'A'..=b'Z' | b'a'..=b'z'),
-            c3 @ (b'A'..=b'Z' | b'a'..=b'z'),
-            c4 @ (b'A'..=b'Z' | b'a'..=b'z'),
-            c5 @ (b'A'..=b'Z' | b'a'..=b'z'),
-          ] = s.as_encoded_bytes() else { panic!("`auto` word must be five ASCII letters") };
-          run_mode = RunMode::Auto(
-            Word::from_bytes([
-              c1.to_ascii_uppercase(),
-              c2.to_ascii_uppercase(),
-              c3.to_ascii_uppercase(),
-              c4.to_ascii_uppercase(),
-              c5.to_ascii_uppercase(),
-            ])
-            .expect("`auto` word must be a Word")
-          );
+          let word = parser.value().expect("`auto` argument must have a word to solve for")
+            .string().expect("`auto` word must be valid unicode");
+          run_mode = RunMode::Auto(word);
+        }
+
+        Long("auto-file") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::AutoFile(PathBuf::from(parser.value().expect("`auto-file` argument must have a path to a word list")));
+        }
+
+        Long("freq-prior") => prior = Some(PathBuf::from(parser.value().expect("`freq-prior` argument must have a path to a word frequency list"))),
+
+        Long("freq") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::Freq;
+        }
+
+        Long("find-opener") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::FindOpener;
+        }
+
+        Long("absurdle") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::Absurdle;
+        }
+
+        Long("practice") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::Practice;
+        }
+
+        Long("daily") => {
+          assert!(matches!(run_mode, RunMode::Interactive), "cannot set run mode more than once");
+          run_mode = RunMode::Daily;
         }
 
         Long("help") => {
+          println!("each turn, input the word you played (the suggestion or any other word), then its feedback, on one line or two");
+          println!("feedback is one symbol per letter: + or g for green, ? or y for yellow, _ or b for grey");
+          println!("  (or the symbols set with --input-symbols, green then yellow then grey)");
           println!("input \"exit\" instead of a word to end the game");
+          println!("input \"undo\" instead of a word to take back the last feedback");
+          println!("input \"skip\" instead of a word to get the next best suggestion");
+          println!("input \"grade GUESS TARGET\" to see the feedback GUESS would get if TARGET were the answer");
+          println!("input \"list\" to see every remaining candidate again, or \"count\" for just how many");
+          println!("input \"match PATTERN\" to list the candidates fitting PATTERN, one letter or ? per position, like ?R?_E");
+          println!("input \"anagram LETTERS\" to list the words containing all of LETTERS, or just \"anagram\" for the letters found so far");
+          println!("with -v, input \"why not WORD?\" to see why WORD isn't a candidate");
+          println!("with --practice or --daily, input just the word, its feedback comes from a hidden answer");
           return;
         }
 
@@ -142,6 +859,23 @@ fn main() {
       }
     }
 
+    assert!(!is_assist || matches!(run_mode, RunMode::Interactive), "`assist` only works in interactive mode");
+    assert!(!is_timing || boards == 1 && matches!(run_mode, RunMode::Interactive | RunMode::Practice | RunMode::Daily | RunMode::Auto(_)), "`timing` only works in interactive and auto mode, with one board");
+    assert!(!show_counts || boards == 1 && matches!(run_mode, RunMode::Interactive | RunMode::Practice | RunMode::Daily | RunMode::Auto(_)), "`show-counts` only works in interactive and auto mode, with one board");
+    // there's no clock to read on wasm32-unknown-unknown
+    #[cfg(feature = "wasm")]
+    assert!(!is_timing, "`timing` isn't available in wasm builds");
+    assert!(!resume || matches!(run_mode, RunMode::Stats(_)), "`resume` only works in stats mode");
+    assert!(boards == 1 || matches!(run_mode, RunMode::Auto(_)) && length == 5, "`boards` only works in auto mode with five-letter words");
+    // Dordle gets 7 turns and Quordle gets 9
+    let max_turns = max_turns.unwrap_or(5 + boards as u32);
+    symbols.output = output_symbols.unwrap_or(palette.feedback());
+    if length != 5 {
+      assert!(dictionary.is_some(), "only five-letter words have an embedded list, use `dict` to provide one");
+      assert!(matches!(run_mode, RunMode::Interactive | RunMode::Practice | RunMode::Daily | RunMode::Auto(_)), "other word lengths only work in interactive, practice and auto mode");
+      assert!(opener.is_none() && !cache_feedback && prior.is_none(), "`opener`, `cache` and `freq-prior` only work with five-letter words");
+    }
+
     if is_verbose && matches!(run_mode, RunMode::Stats(_)) {
       println!("warning: verbose messages are disabled in stats runs");
       is_verbose = false;
@@ -150,87 +884,129 @@ fn main() {
     AppOptions {
       is_verbose,
       is_hardmode,
+      is_lowercase,
+      is_assist,
+      is_timing,
+      show_counts,
+      symbols,
+      palette,
+      max_turns,
+      boards,
+      length,
+      strategy,
+      ranking,
+      log,
+      opener,
+      cache_feedback,
+      batch_size,
+      resume,
+      is_tiebreak,
+      candidate_limit,
+      threads,
+      seed,
+      format,
+      language,
+      dictionary,
+      answers,
+      prior,
       run_mode,
     }
   }).unwrap();
 
-  if let RunMode::Stats(_n) = OPTIONS.get().unwrap().run_mode {assert!(!OPTIONS.get().unwrap().is_verbose, "verbose messages are not permitted in stats run");
-    const BATCH_SIZE: usize = 100;
-    let mut candidates_buf = Some(Vec::new());
-    let mut games: Vec<(bool, Word, ArrayVec<Word, 6>)> = Vec::with_capacity(FIVE_LETTER_WORDS.len());
-    let mut batch = 0;
-    'rounds: for (cycle, word) in (0..BATCH_SIZE).cycle().zip(FIVE_LETTER_WORDS.iter()) {
-      if cycle == 0 {
-        println!("{:3.3}% complete", 100.0*batch as f64/FIVE_LETTER_WORDS.len() as f64);
-        batch += BATCH_SIZE;
-      }
-      let mut guesser = Guesser::new(candidates_buf.take().unwrap());
-      let mut attempts = ArrayVec::<Word, 6>::new();
-      for turn in 1..=6 {
-        let guess = guesser.guess().unwrap();
-        attempts.push(*guess);
-        let stats = check_word(*word, *guess);
-        if guess == word {
-          games.push((true, *word, attempts));
-          candidates_buf = Some(guesser.extract_resources());
-          continue 'rounds;
+  let options = OPTIONS.get().unwrap();
+  #[cfg(feature = "parallel")]
+  if let Some(threads) = options.threads {
+    rayon::ThreadPoolBuilder::new()
+      .num_threads(threads.get())
+      .build_global()
+      .expect("thread pool should only be built once");
+  }
+  dictionary::use_language(options.language);
+  for (path, use_words) in [
+    (&options.dictionary, dictionary::use_custom_guesses as fn(_)),
+    (&options.answers, dictionary::use_custom_answers),
+  ] {
+    // other lengths are loaded when the game starts
+    if options.length == 5 && let Some(path) = path {
+      match dictionary::load_from_path(path) {
+        Ok(words) => use_words(words),
+        Err(e) => {
+          eprintln!("failed to load dictionary {}: {e}", path.display());
+          return;
         }
-        guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i])));
-        guesser.prune(turn);
       }
-      games.push((false, *word, attempts));
-      candidates_buf = Some(guesser.extract_resources());
     }
-
-    // send statistics to TSV
-    {
-      if let Ok(file) = std::fs::File::create("stats.tsv") {
-        use std::io::Write;
-        const FALSE: Word = Word::from_bytes(*b"FALSE").unwrap();
-        let mut buf_writer = std::io::BufWriter::new(file);
-        _ = write!(buf_writer, "\"Word\"\t\"Success\"\t\"Turns\"\t\"Turn 1 word\"\t\"Turn 2 word\"\t\"Turn 3 word\"\t\"Turn 4 word\"\t\"Turn 5 word\"\t\"Turn 6 word\"");
-        for (success, word, attempts) in games.iter() {
-          if *success {
-            _ = write!(buf_writer, "\n\"{}{word}\"\tTRUE\t{}", if word == &FALSE { "'" } else { "" }, attempts.len());
-          } else {
-            _ = write!(buf_writer, "\n\"{}{word}\"\tFALSE\t#N/A", if word == &FALSE { "'" } else { "" });
-          }
-          for attempt in attempts {
-            _ = write!(buf_writer, "\t\"{}{attempt}\"", if attempt == &FALSE { "'" } else { "" });
-          }
-        }
-        _ = buf_writer.flush();
+  }
+  if let Some(path) = &options.prior {
+    match std::fs::read_to_string(path).and_then(|text| dictionary::parse_prior(&text)) {
+      Ok(prior) => dictionary::use_prior(prior),
+      Err(e) => {
+        eprintln!("failed to load word frequencies {}: {e}", path.display());
+        return;
       }
     }
+  }
 
-    let turns: Vec<_> = games.iter()
-      .map(|(success, _, words)| success.then(|| words.len() as u32))
-      .collect();
+  if let Some(opener) = &options.opener && !dictionary::ALLOWED_GUESSES.contains(opener) {
+    eprintln!("opener {opener} is not in the dictionary");
+    return;
+  }
 
-    let mut successes: Vec<_> = turns.iter()
-      .copied()
-      .filter_map(|n| n)
-      .collect();
+  if let RunMode::Stats(n) = OPTIONS.get().unwrap().run_mode {assert!(!OPTIONS.get().unwrap().is_verbose, "verbose messages are not permitted in stats run");
+    let max_turns = OPTIONS.get().unwrap().max_turns;
+    let mut answers: Vec<Word> = ANSWERS.to_vec();
+    if let Some(seed) = OPTIONS.get().unwrap().seed {
+      use rand::{SeedableRng, seq::SliceRandom};
+      answers.shuffle(&mut rand::rngs::StdRng::seed_from_u64(seed));
+    }
+    answers.truncate(n.get());
+    #[cfg(not(feature = "wasm"))]
+    let checkpoint = Some(std::path::Path::new(CHECKPOINT_FILE));
+    #[cfg(feature = "wasm")]
+    let checkpoint = None;
+    let games = match play_stats(&answers, max_turns, OPTIONS.get().unwrap().batch_size, checkpoint, OPTIONS.get().unwrap().resume) {
+      Ok(games) => games,
+      Err(e) => {
+        eprintln!("failed to checkpoint stats in {CHECKPOINT_FILE}: {e}");
+        return;
+      }
+    };
 
-    successes.sort();
+    // the histogram only needs a row for each turn that was actually reached
+    let max_turns = if max_turns == UNLIMITED_TURNS {
+      games.iter().map(|game| game.guesses.len() as u32).max().unwrap_or(1)
+    } else {
+      max_turns
+    };
+    let report = StatsReport::new(max_turns, games);
+    #[cfg(not(feature = "wasm"))]
+    {
+      let format = OPTIONS.get().unwrap().format;
+      if let Ok(file) = std::fs::File::create(format.file_name()) {
+        _ = report.write(format, std::io::BufWriter::new(file));
+      }
+      // the run finished, so the next one starts over
+      _ = std::fs::remove_file(CHECKPOINT_FILE);
+    }
 
-    let won = successes.len();
-    let lost = turns.len() - won;
-    let win_probability = won as f64 / turns.len() as f64;
+    let Summary { won, lost, turns, distribution: ranges, burners } = report.summary();
+    let total = report.games.len();
+    let win_probability = won as f64 / total as f64;
     println!("\
       games won: {won}\n\
       games lost: {lost}\n\
       win probability: {win_probability}\
     ");
+    // whether the tiebreaker's burners pay off
+    let rate = |won: usize, games: usize| if games == 0 { "-".to_string() } else { (won as f64 / games as f64).to_string() };
+    println!("\
+      burners played: {}\n\
+      games with a burner: {} (win probability: {})\n\
+      games without a burner: {} (win probability: {})\
+    ", burners.turns, burners.games, rate(burners.won, burners.games), total - burners.games, rate(won - burners.won, total - burners.games));
 
-    if !successes.is_empty() {
-      let min = successes.first().copied().unwrap();
-      let max = successes.last().copied().unwrap();
+    if let Some(TurnStats { min, max, mean, variance, std_dev, mode, q1, median: q2, q3 }) = turns {
       let range = max - min;
-      let mean = successes.iter().copied().map(|x| x as f64).sum::<f64>() / successes.len() as f64;
-      let q1 = successes[1*successes.len() / 4];
-      let q2 = successes[2*successes.len() / 4];
-      let q3 = successes[3*successes.len() / 4];
       let iqr = q3 - q1;
 
       println!("\
@@ -238,16 +1014,21 @@ fn main() {
         max turns: {max}\n\
         range: {range}\n\
         mean: {mean}\n\
+        variance: {variance}\n\
+        standard deviation: {std_dev}\n\
+        mode: {mode}\n\
         Q1: {q1}\n\
         median: {q2}\n\
         Q3: {q3}\n\
         IQR: {iqr}\
       ");
 
-      let mut slice = &successes[..];
-      const COLORS: [&str; 7] = ["🟪", "🟦", "🟩", "🟨", "🟧", "🟥", "\u{2B1C}"];
-      const COLOR_BAR: &str = "🟥🟥🟥🟥🟥🟥🟧🟧🟧🟧🟧🟧🟧🟨🟨🟨🟨🟨🟨🟨🟨🟩🟩🟩🟩🟩🟩🟩🟩🟦🟦🟦🟦🟦🟦🟦🟪🟪🟪🟪🟪🟪";
-      const SCALE: usize = COLOR_BAR.len()/'🟥'.len_utf8();
+      const SCALE: usize = Palette::BAR_WIDTH;
+      let palette = OPTIONS.get().unwrap().palette;
+      // `len` glyphs colored by `color`, padded out to the full width
+      let bar = |len: usize, color: &dyn Fn(usize) -> char| -> String {
+        (0..SCALE).map(|i| if i < len { color(i) } else { palette.empty() }).collect()
+      };
       const HEADERS: [&str; 3] = [
         "\nwins per turn:\n",
         "\nprobability of winning on a turn:\n",
@@ -259,156 +1040,960 @@ fn main() {
         HEADERS.iter()
           .map(|s| s.len())
           .sum::<usize>() +
-        ("_: 00000 \n".len() + COLOR_BAR.len())*(6*HEADERS.len() + 1)
+        ("__: 00000 \n".len() + SCALE*'🟥'.len_utf8())*(max_turns as usize*HEADERS.len() + 1)
       );
 
-      let mut ranges = [0; 7];
-      for turn in 0..6 {
-        let n = slice.partition_point(|&t| t == turn + 1);
-        ranges[turn as usize] = n;
-        slice = &slice[n..];
-      }
-      ranges[6] = lost;
       let most = ranges.iter().copied().max().unwrap();
 
       use std::fmt::Write;
 
       output.push_str(HEADERS[0]);
       for (turn, n) in ranges.iter().copied().enumerate() {
-        write!(&mut output, "{}: {n:>5} {:⬛<SCALE$}\n",
-          if turn == 6 { 'L' } else { char::from(b'1' + turn as u8) },
-          COLORS[turn as usize].repeat((SCALE as f64*n as f64/most as f64).round() as usize),
+        let label = if turn == max_turns as usize { "L".to_string() } else { (turn + 1).to_string() };
+        let color = palette.turn_color(turn as u32, max_turns);
+        writeln!(&mut output, "{label:>2}: {n:>5} {}",
+          bar((SCALE as f64*n as f64/most as f64).round() as usize, &|_| color),
         ).unwrap();
       }
       output.push_str(HEADERS[1]);
-      for (turn, n) in ranges.iter().take(6).copied().enumerate() {
-        let p = n as f64/turns.len() as f64;
-        write!(&mut output, "{}: {p:>1.3} {:⬛<SCALE$}\n",
+      for (turn, n) in ranges.iter().take(max_turns as usize).copied().enumerate() {
+        let p = n as f64/total as f64;
+        writeln!(&mut output, "{:>2}: {p:>1.3} {}",
           turn + 1,
-          &COLOR_BAR[..'🟥'.len_utf8()*(SCALE as f64*p).round() as usize],
+          bar((SCALE as f64*p).round() as usize, &|i| palette.bar_color(i)),
         ).unwrap();
       }
       output.push_str(HEADERS[2]);
-      let mut contestants = turns.len();
-      for (turn, n) in ranges.iter().take(6).copied().enumerate() {
+      let mut contestants = total;
+      for (turn, n) in ranges.iter().take(max_turns as usize).copied().enumerate() {
         if contestants == 0 {
-          write!(&mut output, "{}: no data, always won before this turn", turn + 1).unwrap();
+          writeln!(&mut output, "{:>2}: no data, always won before this turn", turn + 1).unwrap();
         } else {
           let p = n as f64/contestants as f64;
-          write!(&mut output, "{}: {p:>1.3} {:⬛<SCALE$}\n",
+          writeln!(&mut output, "{:>2}: {p:>1.3} {}",
             turn + 1,
-            &COLOR_BAR[..'🟥'.len_utf8()*(SCALE as f64*p).round() as usize],
+            bar((SCALE as f64*p).round() as usize, &|i| palette.bar_color(i)),
           ).unwrap();
         }
         contestants -= n;
       }
       // output.push_str(HEADERS[3]);
       // let mut p = 0.0;
-      // for (turn, n) in ranges.iter().take(6).copied().enumerate() {
-      //   p += n as f64/turns.len() as f64;
-      //   write!(&mut output, "{}: {p:>1.3} {:⬛<SCALE$}\n",
+      // for (turn, n) in ranges.iter().take(max_turns as usize).copied().enumerate() {
+      //   p += n as f64/total as f64;
+      //   writeln!(&mut output, "{:>2}: {p:>1.3} {}",
       //     turn + 1,
-      //     &COLOR_BAR[..'🟥'.len_utf8()*(SCALE as f64*p).round() as usize],
+      //     bar((SCALE as f64*p).round() as usize, &|i| palette.bar_color(i)),
       //   ).unwrap();
       // }
       // output.push_str(HEADERS[4]);
       // let mut p = 1.0;
-      // for (turn, n) in ranges.iter().take(6).copied().enumerate() {
-      //   p -= n as f64/turns.len() as f64;
-      //   write!(&mut output, "{}: {p:>1.3} {:⬛<SCALE$}\n",
+      // for (turn, n) in ranges.iter().take(max_turns as usize).copied().enumerate() {
+      //   p -= n as f64/total as f64;
+      //   writeln!(&mut output, "{:>2}: {p:>1.3} {}",
       //     turn + 1,
-      //     &COLOR_BAR[..'🟥'.len_utf8()*(SCALE as f64*p).round() as usize],
+      //     bar((SCALE as f64*p).round() as usize, &|i| palette.bar_color(i)),
       //   ).unwrap();
       // }
       print!("{output}");
     }
-  } else {
-    let mut buf = String::with_capacity(12);
+  } else if let RunMode::Freq = &OPTIONS.get().unwrap().run_mode {
+    print!("{}", frequency_table(&ANSWERS));
+  } else if let RunMode::AutoFile(path) = &OPTIONS.get().unwrap().run_mode {
+    let words = match std::fs::read_to_string(path).and_then(|text| dictionary::parse_list(&text)) {
+      Ok(words) => words,
+      Err(e) => {
+        eprintln!("failed to load word list {}: {e}", path.display());
+        return;
+      }
+    };
     let mut guesser = Guesser::new(Vec::new());
-    let mut attempts = Attempts::new();
-
-    for turn in 1..=6 {
-      println!("turn {turn} ({} remaining):", 6 - turn);
-      let Some(s) = guesser.guess() else {
+    apply_options(&mut guesser, OPTIONS.get().unwrap());
+    let max_turns = OPTIONS.get().unwrap().max_turns;
+    for word in words {
+      match play_game(&mut guesser, word, max_turns).turns() {
+        Some(n) => println!("{}: won in {n}", shown(word)),
+        None => println!("{}: lost", shown(word)),
+      }
+    }
+  } else if let RunMode::Auto(words) = &options.run_mode && options.boards > 1 {
+    let answers = match words.split(',').map(|word| word.trim().parse::<Word>()).collect::<Result<Vec<_>, _>>() {
+      Ok(answers) if answers.len() == options.boards => answers,
+      Ok(answers) => {
+        eprintln!("`auto` must have {} comma-separated words for {} boards, found {}", options.boards, options.boards, answers.len());
+        return;
+      }
+      Err(e) => {
+        eprintln!("`auto` words must be 5 ASCII letters: {e}");
+        return;
+      }
+    };
+    let mut guessers: Vec<Guesser> = answers.iter().map(|_| {
+      let mut guesser = Guesser::new(Vec::new());
+      apply_options(&mut guesser, options);
+      guesser
+    }).collect();
+    let (guesses, solved) = play_boards(&mut guessers, &answers, options.max_turns);
+    // emoji are two columns wide
+    let blank = if options.symbols.output.iter().all(char::is_ascii) { "     " } else { "          " };
+    for (turn, guess) in (1..).zip(&guesses) {
+      print!("turn {turn}: {}", shown(*guess));
+      for (&answer, &solved_on) in answers.iter().zip(&solved) {
+        if solved_on.is_none_or(|n| turn <= n) {
+          print!(" {}", options.symbols.display(check_word(answer, *guess)));
+        } else {
+          print!(" {blank}");
+        }
+      }
+      println!();
+    }
+    for (answer, solved_on) in answers.iter().zip(solved) {
+      match solved_on {
+        Some(n) => println!("{}: solved on turn {n}", shown(*answer)),
+        None => println!("{}: not solved", shown(*answer)),
+      }
+    }
+  } else if let RunMode::FindOpener = &options.run_mode {
+    let mut guesser = Guesser::new(Vec::new());
+    apply_options(&mut guesser, options);
+    for (rank, (word, bits)) in (1..).zip(guesser.best_openers(20)) {
+      println!("{rank:>2}. {} {bits:.3} bits, {:.1} candidates expected to remain", shown(word), guesser.expected_remaining(word));
+    }
+  } else if let RunMode::Absurdle = &options.run_mode {
+    let mut guesser = Guesser::new(Vec::new());
+    apply_options(&mut guesser, options);
+    let mut host = Absurdle::new(ANSWERS.to_vec());
+    let mut attempts = Attempts::with_capacity(options.max_turns);
+    attempts.set_symbols(options.symbols);
+    for turn in 1..=options.max_turns {
+      let Some(&guess) = guesser.guess() else {
         println!("no such word exists in my dictionary");
         return;
       };
-      println!("suggestion: {s}");
-      let feedback = if let RunMode::Auto(g) = &OPTIONS.get().unwrap().run_mode {
-        let fb = check_word(*g, *s);
-        std::array::from_fn(|i| (s[i], fb[i]))
+      let stats = host.respond(guess);
+      attempts.push(stats);
+      println!("turn {turn}: {} {} ({} answers left)", shown(guess), options.symbols.display(stats), host.remaining().len());
+      let analyzed = guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i])))
+        .expect("the host's feedback should never be contradictory");
+      if analyzed.solved {
+        println!("{}", attempts.share_text(None));
+        println!("cornered the host in {turn} turns");
+        return;
+      }
+      guesser.prune(turn);
+    }
+    println!("{}", attempts.share_text(None));
+    println!("survived {} turns, {} answers still possible", options.max_turns, host.remaining().len());
+  } else if let RunMode::Replay(path) = &options.run_mode {
+    let games = match std::fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|text| read_log(&text)) {
+      Ok(games) => games,
+      Err(e) => {
+        eprintln!("failed to read game log {}: {e}", path.display());
+        return;
+      }
+    };
+    for game in games {
+      let answer = game.answer.map_or_else(|| "?????".to_string(), |w| shown(w).to_string());
+      if game.is_won() {
+        println!("{answer}: won in {}", game.turns.len());
       } else {
-        buf.clear();
-        stdin().read_line(&mut buf).unwrap();
-        buf.truncate(buf.trim_end().len());
-        if buf.trim_end() == "exit" { return; }
-        stdin().read_line(&mut buf).unwrap();
-        buf.truncate(buf.trim_end().len());
-        assert!(buf.len() == 10);
-        let bytes = buf.as_bytes();
-        std::array::from_fn(|i| (
-          Letter::from_u8(bytes[i].to_ascii_uppercase())
-            .expect("unknown format"),
-          match bytes[i + 5] {
-            b'+' => LetterFeedback::Confirmed,
-            b'?' => LetterFeedback::Required,
-            b'_' => LetterFeedback::Excluded,
-            _ => panic!("unknown format"),
-          },
-        ))
+        println!("{answer}: lost");
+      }
+      let mut attempts = Attempts::with_capacity(game.turns.len() as u32);
+      attempts.set_symbols(options.symbols);
+      for (_, feedback) in &game.turns {
+        attempts.push(*feedback);
+      }
+      println!("{attempts}\n");
+    }
+  } else {
+    match options.length {
+      4 => play_with_dict::<4>(options),
+      5 => {
+        let mut guesser = Guesser::new_with_lists(ANSWERS.as_slice(), dictionary::ALLOWED_GUESSES.as_slice());
+        apply_options(&mut guesser, options);
+        play(guesser, dictionary::ALLOWED_GUESSES.as_slice(), options);
+      }
+      6 => play_with_dict::<6>(options),
+      7 => play_with_dict::<7>(options),
+      _ => unreachable!("length is checked when parsing arguments"),
+    }
+  }
+}
+
+/// Play `N`-letter words from `--dict` and `--answers`, since only five-letter lists are embedded
+fn play_with_dict<const N: usize>(options: &AppOptions) {
+  let load = |path: &PathBuf| match dictionary::load_from_path::<N>(path) {
+    Ok(words) => Some(&*words.leak()),
+    Err(e) => {
+      eprintln!("failed to load dictionary {}: {e}", path.display());
+      None
+    }
+  };
+  let Some(guesses) = load(options.dictionary.as_ref().expect("other lengths should require a dictionary")) else {
+    return;
+  };
+  let answers = match &options.answers {
+    Some(path) => {
+      let Some(answers) = load(path) else {
+        return;
       };
-      attempts.push(WordFeedback::new(feedback.map(|(_, stat)| stat)));
-      if attempts.0.last() == Some(&WordFeedback::new([LetterFeedback::Confirmed; 5])) {
-        println!("{attempts}");
-        let word = Word(feedback.map(|(ch, _)| ch));
-        println!("success! winning word: {word}");
+      answers
+    }
+    None => guesses,
+  };
+  let mut guesser = Guesser::new_with_lists(answers, guesses);
+  apply_strategy_options(&mut guesser, options);
+  play(guesser, guesses, options);
+}
+
+/// Play one interactive or auto game, suggesting from `guesses`
+fn play<const N: usize>(mut guesser: Guesser<N>, guesses: &[Word<N>], options: &AppOptions) {
+  let log_game = |game: &PlayedGame<N>| if let Some(path) = &options.log
+    && let Err(e) = game.append_to(path) {
+    eprintln!("failed to write game log {}: {e}", path.display());
+  };
+  #[cfg(feature = "wasm")]
+  if !matches!(options.run_mode, RunMode::Auto(_)) {
+    eprintln!("wasm builds can't read feedback from stdin, use --auto");
+    return;
+  }
+  let answer = match &options.run_mode {
+    RunMode::Auto(word) => match word.parse::<Word<N>>() {
+      Ok(word) => Some(word),
+      Err(e) => {
+        eprintln!("`auto` word must be {N} ASCII letters: {e}");
         return;
       }
-      guesser.analyze(feedback);
-      guesser.prune(turn);
-      print!("candidates:");
-      for (n, word) in (0..7).cycle().zip(guesser.candidates()) {
-        if n == 0 { println!(); }
-        print!("{word} ");
+    },
+    _ => None,
+  };
+  let hidden = if let RunMode::Practice | RunMode::Daily = options.run_mode {
+    let hidden = if let RunMode::Daily = options.run_mode {
+      let puzzle = daily_puzzle(unix_time());
+      println!("daily puzzle #{puzzle}");
+      pick_daily_answer(guesser.answers(), puzzle)
+    } else {
+      pick_practice_answer(&guesser.candidates()[usize::from(guesser.burner().is_some())..], options.seed)
+    };
+    let Some(hidden) = hidden else {
+      eprintln!("there are no answers to pick from");
+      return;
+    };
+    println!("guess the {N}-letter word");
+    Some(hidden)
+  } else {
+    None
+  };
+  let mut buf = String::with_capacity(12);
+  let max_turns = options.max_turns;
+  let mut attempts = Attempts::with_capacity(max_turns);
+  attempts.set_symbols(options.symbols);
+
+  let mut game = PlayedGame {
+    answer: answer.or(hidden),
+    turns: Vec::with_capacity(turn_capacity(max_turns)),
+  };
+  let mut turn = 1;
+  let mut solve_time = Duration::ZERO;
+  'turns: while turn <= max_turns {
+    if max_turns == UNLIMITED_TURNS {
+      println!("turn {turn}:");
+    } else {
+      println!("turn {turn} ({} remaining):", max_turns - turn);
+    }
+    let started = options.is_timing.then(Instant::now);
+    let Some(suggestion) = guesser.suggest() else {
+      println!("no such word exists in my dictionary");
+      if let Some(answer) = answer.or(hidden) {
+        println!("{}", loss_report(&guesser, answer, options.is_verbose));
+      }
+      return;
+    };
+    let mut turn_time = started.map_or(Duration::ZERO, |t| t.elapsed());
+    let s = &suggestion.word;
+    if !options.is_assist && hidden.is_none() {
+      if suggestion.is_burner {
+        println!("suggestion: {} (tiebreaker to split {} candidates, can't be the answer)", shown(*s), suggestion.candidates_remaining);
+      } else {
+        println!("suggestion: {}", shown(*s));
+      }
+      verbose_println!("expected candidates remaining: {:.2} ({:.3} bits)", suggestion.expected_remaining, guesser.information_gain(*s));
+      if let [_, rest @ ..] = guesser.suggestions(5) && !rest.is_empty() {
+        verbose_print!("runners-up:");
+        for word in rest {
+          verbose_print!(" {}", shown(*word));
+        }
+        verbose_println!();
+      }
+    }
+    let feedback = if let Some(g) = answer {
+      let fb = check_word(g, *s);
+      std::array::from_fn(|i| (s[i], fb[i]))
+    } else {
+      loop {
+        // stdin is unlocked again before the entry is handled, so `list` can ask to continue
+        let entry = match hidden {
+          Some(hidden) => read_practice_entry(&mut stdin().lock(), &mut buf, hidden),
+          None => read_entry(&mut stdin().lock(), &mut buf, &options.symbols),
+        };
+        match entry {
+          Ok(Some(Entry::Played(played))) => {
+            let word = Word(played.map(|(ch, _)| ch));
+            if !guesses.contains(&word) {
+              eprintln!("{} is not in the dictionary, try again", shown(word));
+              continue;
+            }
+            // the user may have played something other than the suggestion
+            if guesser.guess() != Some(&word)
+              && let Some(reason) = guesser.explain_rejection(word)
+            {
+              verbose_println!("{} can't be the answer: {reason}", shown(word));
+            }
+            break played;
+          }
+          Ok(Some(Entry::WhyNot(word))) => {
+            if !options.is_verbose {
+              eprintln!("`why not` is only available in verbose mode (-v)");
+            } else if let Some(reason) = guesser.explain_rejection(word) {
+              verbose_println!("{} was ruled out: {reason}", shown(word));
+            } else {
+              verbose_println!("{} is still a candidate", shown(word));
+            }
+          }
+          Ok(Some(Entry::Undo)) => {
+            if turn > 1 && guesser.undo(turn - 1) {
+              turn -= 1;
+              attempts.pop();
+              game.turns.pop();
+              println!("took back turn {turn}");
+              continue 'turns;
+            }
+            eprintln!("nothing to undo");
+          }
+          Ok(Some(Entry::Grade(guess, target))) => {
+            println!("{} {}", shown(guess), options.symbols.display(check_word(target, guess)));
+          }
+          Ok(Some(Entry::Skip)) => {
+            if guesser.skip().is_some() {
+              continue 'turns;
+            }
+            eprintln!("no more suggestions");
+          }
+          Ok(Some(Entry::List)) => {
+            let rows = candidate_rows(guesser.candidates(), None);
+            for (i, page) in rows.chunks(LIST_PAGE_ROWS).enumerate() {
+              if i > 0 {
+                let left = guesser.candidates().len() - i*LIST_PAGE_ROWS*CANDIDATES_PER_ROW;
+                println!("-- {left} more, press enter to continue or input \"q\" to stop --");
+                buf.clear();
+                if stdin().read_line(&mut buf).map_or(true, |n| n == 0) || buf.trim() == "q" {
+                  break;
+                }
+              }
+              for row in page {
+                println!("{row}");
+              }
+            }
+          }
+          Ok(Some(Entry::Count)) => println!("{} candidates remaining", guesser.candidates().len()),
+          Ok(Some(Entry::Match(pattern))) => match guesser.matching(&pattern) {
+            Ok(words) => {
+              println!("{} candidates match {pattern}:", words.len());
+              for row in candidate_rows(&words, options.candidate_limit) {
+                println!("{row}");
+              }
+            }
+            Err(e) => eprintln!("{e}, try again"),
+          },
+          Ok(Some(Entry::Anagram(letters))) => {
+            let letters = letters.unwrap_or_else(|| guesser.known_letters());
+            let words = dictionary::words_containing(guesses, &letters);
+            println!("{} words contain {}:", words.len(), letters.iter().map(Letter::to_string).collect::<String>());
+            for row in candidate_rows(&words, options.candidate_limit) {
+              println!("{row}");
+            }
+          }
+          Ok(None) => {
+            println!("stopped before finding the answer");
+            return;
+          }
+          Err(e) => eprintln!("{e}, try again"),
+        }
+      }
+    };
+    let stats = WordFeedback::new(feedback.map(|(_, stat)| stat));
+    let started = options.is_timing.then(Instant::now);
+    let analyzed = match guesser.analyze(feedback) {
+      Ok(analyzed) => analyzed,
+      Err(e) => {
+        println!("that feedback contradicts an earlier one: {e}\ntry again");
+        continue;
+      }
+    };
+    let word = Word(feedback.map(|(ch, _)| ch));
+    attempts.push(stats);
+    game.turns.push((word, stats));
+    if analyzed.solved {
+      println!("{}", attempts.share_text(None));
+      println!("success! winning word: {}", shown(word));
+      if options.is_timing {
+        println!("total solve time: {:.2?}", solve_time + turn_time);
+      }
+      game.answer = Some(word);
+      log_game(&game);
+      return;
+    }
+    let pruned = guesser.prune(turn);
+    turn_time += started.map_or(Duration::ZERO, |t| t.elapsed());
+    solve_time += turn_time;
+    if options.is_timing {
+      println!("turn {turn} took {turn_time:.2?}");
+    }
+    if options.is_assist || options.show_counts {
+      println!("{} candidates remaining", pruned.after);
+    }
+    verbose_println!("ruled out {} of {} candidates", pruned.eliminated(), pruned.before);
+    if hidden.is_none() {
+      println!("candidates:");
+      for row in candidate_rows(guesser.candidates(), options.candidate_limit) {
+        println!("{row}");
       }
-      println!();
-      println!("{attempts}");
     }
-    println!("game over");
+    println!("{attempts}");
+    turn += 1;
   }
+  println!("game over");
+  if let Some(answer) = answer.or(hidden) {
+    println!("{}", loss_report(&guesser, answer, options.is_verbose));
+  }
+  if options.is_timing {
+    println!("total solve time: {solve_time:.2?}");
+  }
+  log_game(&game);
+}
+
+/// Rows of candidates shown by `list` before asking to continue
+const LIST_PAGE_ROWS: usize = 20;
+
+const CANDIDATES_PER_ROW: usize = 7;
+
+/// Candidates printed after each turn, unless `--candidate-limit` or `--show-all` says otherwise
+const DEFAULT_CANDIDATE_LIMIT: usize = 50;
+
+/// `words` in rows, the way candidates are shown after each turn
+///
+/// Past `limit` words, the last row says how many more there are instead.
+fn candidate_rows<const N: usize>(words: &[Word<N>], limit: Option<usize>) -> Vec<String> {
+  let limit = limit.map_or(words.len(), |limit| limit.min(words.len()));
+  let mut rows: Vec<String> = words[..limit].chunks(CANDIDATES_PER_ROW)
+    .map(|row| row.iter().map(|&word| shown(word).to_string()).collect::<Vec<_>>().join(" "))
+    .collect();
+  if limit < words.len() {
+    rows.push(format!("... and {} more", words.len() - limit));
+  }
+  rows
+}
+
+/// Restate the answer the guesser failed to find, and with `is_verbose`, whether it was still a candidate
+///
+/// An answer that was never a candidate points at the word lists rather than the strategy.
+fn loss_report<const N: usize>(guesser: &Guesser<N>, answer: Word<N>, is_verbose: bool) -> String {
+  let mut report = format!("the word was {}", shown(answer));
+  if is_verbose {
+    if guesser.candidates().contains(&answer) {
+      report += &format!("\n{} was still a candidate, the strategy ran out of turns", shown(answer));
+    } else {
+      report += &format!("\n{} was not a candidate, it may be missing from the answer list", shown(answer));
+    }
+  }
+  report
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::{dictionary::FIVE_LETTER_WORDS, guess::Guesser, play::{self, check_word}, Attempts};
-  use rand::{prelude::*, rng};
-  use rayon::prelude::*;
+  use wordle_helper::{dictionary::{ALLOWED_GUESSES, ANSWERS}, guess::{Guesser, LetterFeedback, WordFeedback}, play::{self, check_word}, stats::StatsReport, word::{Letter, Word}};
+  use crate::Attempts;
+  use rand::{prelude::*, rngs::StdRng};
+  use std::num::NonZeroUsize;
+  use super::{apply_strategy_options, candidate_rows, checkpoint_header, daily_puzzle, pick_daily_answer, frequency_table, loss_report, parse_played, parse_symbols, pick_practice_answer, play_boards, play_game, play_stats, read_entry, read_practice_entry, AppOptions, Entry, FeedbackSymbols, Language, Palette, Ranking, RunMode, StatsFormat, Strategy, DEFAULT_BATCH_SIZE, DEFAULT_CANDIDATE_LIMIT, OPTIONS, UNLIMITED_TURNS};
   extern crate test;
 
+  /// Set `WORDLE_SEED` to replay a failing run
+  fn seeded_rng() -> StdRng {
+    let seed = std::env::var("WORDLE_SEED").ok()
+      .map(|s| s.parse().expect("WORDLE_SEED should be a number"))
+      .unwrap_or_else(|| rand::rng().random());
+    println!("seed: {seed}");
+    StdRng::seed_from_u64(seed)
+  }
+
+  fn init_options() {
+    OPTIONS.get_or_init(default_options);
+  }
+
+  fn default_options() -> AppOptions {
+    AppOptions {
+      is_verbose: false,
+      is_hardmode: false,
+      is_lowercase: false,
+      is_assist: false,
+      is_timing: false,
+      show_counts: false,
+      symbols: FeedbackSymbols::DEFAULT,
+      palette: Palette::Emoji,
+      max_turns: 6,
+      boards: 1,
+      length: 5,
+      strategy: Strategy::Frequency,
+      ranking: Ranking::Positional,
+      log: None,
+      opener: None,
+      cache_feedback: false,
+      batch_size: DEFAULT_BATCH_SIZE,
+      resume: false,
+      is_tiebreak: true,
+      candidate_limit: Some(DEFAULT_CANDIDATE_LIMIT),
+      threads: None,
+      seed: None,
+      format: StatsFormat::Tsv,
+      language: Language::English,
+      dictionary: None,
+      answers: None,
+      prior: None,
+      run_mode: RunMode::Interactive,
+    }
+  }
+
   #[bench]
   fn stats_benchmark(b: &mut test::Bencher) {
-    let guesses = &FIVE_LETTER_WORDS[..];
-    let words = &FIVE_LETTER_WORDS[..64];
+    let guesses = &ALLOWED_GUESSES[..];
+    let words = &ANSWERS[..64];
     let n = guesses.len()*words.len();
-    let mut buffer = Vec::with_capacity(n);
+    let mut buffer = vec![WordFeedback::new([LetterFeedback::Excluded; 5]); n];
+    b.iter(|| {
+      play::grade_many(test::black_box(guesses), test::black_box(words), &mut buffer).unwrap();
+    });
+  }
+
+  #[bench]
+  fn grade_cached(b: &mut test::Bencher) {
+    let guesses = &ALLOWED_GUESSES[..1024];
+    let words = &ANSWERS[..64];
+    let matrix = &play::FeedbackMatrix::new(guesses, words, NonZeroUsize::MIN);
+    let columns: Vec<_> = words.iter().map(|w| matrix.column(w).unwrap()).collect();
+    let mut buffer = Vec::with_capacity(guesses.len()*words.len());
     b.iter(|| {
       buffer.clear();
-      let it = play::grade_many(
-        test::black_box(guesses),
-        test::black_box(words),
-      ).map(|(_, _, x)| x);
-      buffer.par_extend(it);
+      buffer.extend((0..guesses.len()).flat_map(|row| columns.iter().map(move |&col| matrix.get(row, col))));
+      test::black_box(&buffer);
+    });
+  }
+
+  /// A guess with greens, yellows and greys, so every kind of constraint gets checked
+  fn bench_feedback() -> [(wordle_helper::Letter, LetterFeedback); 5] {
+    let (guess, answer) = (Word::from_bytes(*b"CRANE").unwrap(), Word::from_bytes(*b"TRACE").unwrap());
+    let stats = check_word(answer, guess);
+    std::array::from_fn(|i| (guess[i], stats[i]))
+  }
+
+  #[bench]
+  fn prune_benchmark(b: &mut test::Bencher) {
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.analyze(bench_feedback()).unwrap();
+    let state = guesser.export_state();
+    b.iter(|| {
+      // refills the full candidate set with the same constraint
+      guesser.import_state(&state).unwrap();
+      // on the last turn, so no time is spent looking for a burner
+      guesser.prune(test::black_box(6));
+      test::black_box(guesser.candidates());
     });
   }
 
+  #[bench]
+  fn sort_benchmark(b: &mut test::Bencher) {
+    // the whole answer list, like the first prune of a game sees
+    let mut words = ANSWERS.to_vec();
+    b.iter(|| {
+      words.copy_from_slice(&ANSWERS);
+      wordle_helper::dictionary::sort_by_frequency(test::black_box(&mut words));
+    });
+  }
+
+  #[bench]
+  fn tiebreak_benchmark(b: &mut test::Bencher) {
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.analyze(bench_feedback()).unwrap();
+    let state = guesser.export_state();
+    guesser.prune(6);
+    assert!(Guesser::<5>::DEFAULT_TIEBREAK_WINDOW.contains(&guesser.candidates().len()), "{}", guesser.candidates().len());
+    b.iter(|| {
+      guesser.import_state(&state).unwrap();
+      // early enough in the game that every allowed guess is tried as a burner
+      guesser.prune(test::black_box(1));
+      test::black_box(guesser.candidates());
+    });
+  }
+
+  #[bench]
+  fn consistency_benchmark(b: &mut test::Bencher) {
+    let mut guesser = Guesser::new(Vec::new());
+    // all greys, so every word gets as far as the excluded letter check
+    let (guess, answer) = (Word::from_bytes(*b"CLOUD").unwrap(), Word::from_bytes(*b"SHAKE").unwrap());
+    let stats = check_word(answer, guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i]))).unwrap();
+    b.iter(|| {
+      test::black_box(ALLOWED_GUESSES.iter().filter(|&&word| guesser.is_consistent(test::black_box(word))).count())
+    });
+  }
+
+  #[bench]
+  fn analyze_benchmark(b: &mut test::Bencher) {
+    // no candidates, so resetting between runs costs next to nothing
+    let mut guesser = Guesser::new_with_lists(&[], &ALLOWED_GUESSES);
+    let feedback = bench_feedback();
+    b.iter(|| {
+      guesser.reset();
+      guesser.analyze(test::black_box(feedback)).unwrap();
+    });
+  }
+
+  #[test]
+  fn test_share_text() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let mut attempts = Attempts::new();
+    attempts.push(WordFeedback::new([B, Y, B, B, G]));
+    assert_eq!(attempts.share_text(Some(1234)), "Wordle 1234 X/6\n\n⬜🟨⬜⬜🟩");
+    attempts.push(WordFeedback::new([G; 5]));
+    assert_eq!(attempts.share_text(Some(1234)), "Wordle 1234 2/6\n\n⬜🟨⬜⬜🟩\n🟩🟩🟩🟩🟩");
+    attempts.set_symbols(FeedbackSymbols { output: Palette::Ascii.feedback(), ..FeedbackSymbols::DEFAULT });
+    assert_eq!(attempts.to_string(), ".+..#\n#####");
+    assert_eq!(Attempts::<5>::with_capacity(4).share_text(None), "Wordle X/4\n\n");
+    let mut attempts = Attempts::with_capacity(UNLIMITED_TURNS);
+    attempts.push(WordFeedback::new([LetterFeedback::Confirmed; 5]));
+    assert_eq!(attempts.share_text(None), "Wordle 1\n\n🟩🟩🟩🟩🟩");
+  }
+
+  #[test]
+  fn test_attempts_iter() {
+    use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+    let rows = [WordFeedback::new([B, Y, B, B, G]), WordFeedback::new([G; 5])];
+    let mut attempts = Attempts::new();
+    for row in rows {
+      attempts.push(row);
+    }
+    assert_eq!(attempts.iter().copied().collect::<Vec<_>>(), rows);
+    let mut html = String::new();
+    for row in &attempts {
+      html += "<tr>";
+      for col in row.iter() {
+        html += if *col == G { "g" } else if *col == Y { "y" } else { "b" };
+      }
+      html += "</tr>";
+    }
+    assert_eq!(html, "<tr>bybbg</tr><tr>ggggg</tr>");
+  }
+
+  #[test]
+  fn test_attempts_display() {
+    use LetterFeedback::{Confirmed as G, Excluded as B};
+    // more rows than the usual six, of four-letter words
+    let mut attempts = Attempts::<4>::with_capacity(10);
+    assert_eq!(attempts.to_string(), "");
+    for _ in 0..9 {
+      attempts.push(WordFeedback::new([B; 4]));
+    }
+    attempts.push(WordFeedback::new([G; 4]));
+    let text = attempts.to_string();
+    assert_eq!(text.lines().count(), 10);
+    assert_eq!(text.matches('\n').count(), 9, "rows are separated, not terminated");
+    assert_eq!(text.lines().last(), Some("🟩🟩🟩🟩"));
+    assert!(attempts.share_text(None).starts_with("Wordle 10/10\n\n"));
+  }
+
+  #[test]
+  fn test_parse_symbols() {
+    assert_eq!(parse_symbols("gyb"), Some(['b', 'y', 'g']));
+    assert_eq!(parse_symbols("🟦🟧⬛"), Some(['⬛', '🟧', '🟦']));
+    assert_eq!(parse_symbols("gy"), None);
+    assert_eq!(parse_symbols("gybx"), None);
+  }
+
+  #[test]
+  fn test_frequency_table() {
+    let words = ["CRANE", "SLATE", "TRACE"].map(|s| s.parse::<Word>().unwrap());
+    let table = frequency_table(&words);
+    let mut lines = table.lines();
+    assert_eq!(lines.next(), Some("letter      1      2      3      4      5  total"));
+    assert_eq!(lines.next(), Some("A           0      0      3      0      0      3"));
+    assert_eq!(lines.next(), Some("E           0      0      0      0      3      3"));
+    assert_eq!(table.lines().count(), 27);
+    assert!(table.ends_with("Z           0      0      0      0      0      0\n"));
+  }
+
+  #[test]
+  fn test_read_entry() {
+    let mut input = std::io::Cursor::new("crane\n_?__+\nslat\ncrane\n+bbgz\nslate\r\n+++++\nstale\n");
+    let mut buf = String::new();
+    let crane: Word = "CRANE".parse().unwrap();
+    assert_eq!(read_entry::<5>(&mut input, &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Played(parse_played("CRANE _?__+", &FeedbackSymbols::DEFAULT).unwrap()))));
+    assert_eq!(read_entry::<5>(&mut input, &mut buf, &FeedbackSymbols::DEFAULT),
+      Err("invalid word `slat`: expected 5 letters, found 4 (expected a 5-letter guess then 5 feedback symbols)".to_string()));
+    assert!(read_entry::<5>(&mut input, &mut buf, &FeedbackSymbols::DEFAULT).is_err(), "feedback has an unknown symbol");
+    assert_eq!(read_entry::<5>(&mut input, &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Played(parse_played("SLATE +++++", &FeedbackSymbols::DEFAULT).unwrap()))));
+    assert_eq!(read_entry::<5>(&mut input, &mut buf, &FeedbackSymbols::DEFAULT), Ok(None), "input ended before the feedback");
+    assert_eq!(read_entry::<5>(&mut input, &mut buf, &FeedbackSymbols::DEFAULT), Ok(None));
+    assert_eq!(read_entry::<5>(&mut "exit\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(None));
+    assert_eq!(read_entry::<5>(&mut "why not crane?\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::WhyNot(crane))));
+    assert!(read_entry::<5>(&mut "why not cran?\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT).is_err());
+    assert_eq!(read_entry::<5>(&mut "undo\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Undo)));
+    assert_eq!(read_entry::<5>(&mut "skip\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Skip)));
+    assert_eq!(read_entry::<5>(&mut "list\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::List)));
+    assert_eq!(read_entry::<5>(&mut "count\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Count)));
+    assert_eq!(read_entry::<5>(&mut "anagram\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Anagram(None))));
+    assert_eq!(read_entry::<5>(&mut "anagram rRa\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Anagram(Some(vec![Letter::R, Letter::R, Letter::A])))));
+    assert!(read_entry::<5>(&mut "anagram r2\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT).is_err());
+    assert_eq!(read_entry::<5>(&mut "match ?R?_E\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Match("?R?_E".to_string()))));
+    assert_eq!(read_entry::<5>(&mut "grade slate crane\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Grade("SLATE".parse().unwrap(), crane))));
+    assert!(read_entry::<5>(&mut "grade slate\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT).is_err());
+    assert!(read_entry::<5>(&mut "grade slate cran\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT).is_err());
+    assert_eq!(read_entry::<6>(&mut "planet\n++?__+\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Played(parse_played("PLANET ++?__+", &FeedbackSymbols::DEFAULT).unwrap()))));
+    assert_eq!(read_entry::<6>(&mut "planet ++?__+\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Played(parse_played("PLANET ++?__+", &FeedbackSymbols::DEFAULT).unwrap()))));
+    assert!(read_entry::<6>(&mut "crane\n+++++\n".as_bytes(), &mut buf, &FeedbackSymbols::DEFAULT).is_err());
+  }
+
+  #[test]
+  fn test_parse_played() {
+    use wordle_helper::{guess::LetterFeedback::*, word::Letter::*};
+    let crane = [(C, Excluded), (R, Required), (A, Excluded), (N, Excluded), (E, Confirmed)];
+    assert_eq!(parse_played("CRANE _?__+", &FeedbackSymbols::DEFAULT), Ok(crane));
+    assert_eq!(parse_played("  crane \t bybbg ", &FeedbackSymbols::DEFAULT), Ok(crane));
+    assert_eq!(read_entry::<5>(&mut "CRANE _?__+\n".as_bytes(), &mut String::new(), &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Played(crane))));
+    assert_eq!(read_entry::<5>(&mut "crane\n_?__+\n".as_bytes(), &mut String::new(), &FeedbackSymbols::DEFAULT), Ok(Some(Entry::Played(crane))));
+    assert!(parse_played::<5>("CRANE", &FeedbackSymbols::DEFAULT).is_err(), "no feedback");
+    assert!(parse_played::<5>("CRANE _?_", &FeedbackSymbols::DEFAULT).is_err(), "feedback is too short");
+    assert!(parse_played::<5>("CRANE _? _+", &FeedbackSymbols::DEFAULT).is_err(), "feedback is split up");
+    assert!(parse_played::<5>("_?__+ CRANE", &FeedbackSymbols::DEFAULT).is_err(), "backwards");
+    // `--input-symbols` swapping green and yellow
+    let swapped = FeedbackSymbols { input: ['_', '+', '?'], ..FeedbackSymbols::DEFAULT };
+    assert_eq!(parse_played("CRANE _+__?", &swapped), Ok(crane));
+    assert_eq!(parse_played::<5>("CRANE _+__x", &swapped),
+      Err("invalid feedback `_+__x`: unknown feedback symbol 'x', expected one of `+?_` or `gyb`, or `?+_` (expected a 5-letter guess then 5 feedback symbols)".to_string()));
+  }
+
+  #[test]
+  fn test_assist_custom_list() {
+    let words: &'static [Word] = wordle_helper::dictionary::parse_list("árbol cañón perro\ngatos queso vacas\n").unwrap().leak();
+    let options = AppOptions { is_assist: true, strategy: Strategy::Entropy, ..default_options() };
+    let mut guesser = Guesser::new_with_lists(words, words);
+    apply_strategy_options(&mut guesser, &options);
+    let [played, answer] = ["PERRO", "QUESO"].map(|s| s.parse::<Word>().unwrap());
+    let stats = check_word(answer, played);
+    guesser.analyze(std::array::from_fn(|i| (played[i], stats[i]))).unwrap();
+    guesser.prune(1);
+    assert_eq!(guesser.burner(), None, "assist mode should never look for a burner");
+    assert_eq!(guesser.candidates(), [answer]);
+  }
+
+  #[test]
+  fn test_daily_puzzle() {
+    // 2021-06-19 was the first, 2026-10-15 the 1944th after it
+    assert_eq!(daily_puzzle(1_624_060_800), 0);
+    assert_eq!(daily_puzzle(1_624_060_800 + 24*60*60 - 1), 0);
+    assert_eq!(daily_puzzle(1_792_022_400), 1944);
+    assert_eq!(daily_puzzle(1_792_022_400 + 12*60*60), 1944);
+    assert_eq!(daily_puzzle(0), 0);
+  }
+
+  #[test]
+  fn test_daily_answer() {
+    let pick = pick_daily_answer(&ANSWERS, 1944).unwrap();
+    // pinned, so the daily word can't change under anyone
+    assert_eq!(pick.to_string(), "JIVER");
+    let mut shuffled = ANSWERS.to_vec();
+    shuffled.shuffle(&mut seeded_rng());
+    assert_eq!(pick_daily_answer(&shuffled, 1944), Some(pick));
+    assert_eq!(pick_daily_answer::<5>(&[], 1944), None);
+
+    // ranking and the opener only reorder the candidates, not the answers
+    let mut ranked = Guesser::new(Vec::new());
+    apply_strategy_options(&mut ranked, &AppOptions { ranking: Ranking::Blended, ..default_options() });
+    ranked.set_opener("CRANE".parse().unwrap());
+    ranked.prune(1);
+    assert_eq!(pick_daily_answer(ranked.answers(), 1944), Some(pick));
+  }
+
+  #[test]
+  fn test_practice() {
+    let answer = pick_practice_answer(&ANSWERS, Some(4)).unwrap();
+    assert_eq!(pick_practice_answer(&ANSWERS, Some(4)), Some(answer));
+    assert!(ANSWERS.contains(&answer));
+    assert_eq!(pick_practice_answer::<5>(&[], Some(4)), None);
+
+    let crane: Word = "CRANE".parse().unwrap();
+    let mut buf = String::new();
+    let graded = check_word(answer, crane);
+    assert_eq!(
+      read_practice_entry(&mut "crane\n".as_bytes(), &mut buf, answer),
+      Ok(Some(Entry::Played(std::array::from_fn(|i| (crane[i], graded[i]))))),
+    );
+    // commands still work, but feedback can't be given
+    assert_eq!(read_practice_entry(&mut "undo\n".as_bytes(), &mut buf, answer), Ok(Some(Entry::Undo)));
+    assert!(read_practice_entry(&mut "crane +____\n".as_bytes(), &mut buf, answer).is_err());
+    assert_eq!(read_practice_entry(&mut "exit\n".as_bytes(), &mut buf, answer), Ok(None));
+    assert_eq!(read_practice_entry(&mut "".as_bytes(), &mut buf, answer), Ok(None));
+  }
+
+  #[test]
+  fn test_candidate_rows() {
+    let rows = candidate_rows(&ANSWERS[..15], None);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0], ANSWERS[..7].iter().map(Word::to_string).collect::<Vec<_>>().join(" "));
+    assert_eq!(rows[2], ANSWERS[14].to_string());
+    assert!(candidate_rows::<5>(&[], None).is_empty());
+
+    assert_eq!(candidate_rows(&ANSWERS[..15], Some(15)), rows);
+    let capped = candidate_rows(&ANSWERS[..15], Some(9));
+    assert_eq!(capped[0], rows[0]);
+    assert_eq!(capped[1], format!("{} {}", ANSWERS[7], ANSWERS[8]));
+    assert_eq!(capped[2], "... and 6 more");
+    assert_eq!(candidate_rows(&ANSWERS[..15], Some(0)), ["... and 15 more"]);
+  }
+
+  #[test]
+  fn test_loss_report() {
+    init_options();
+    let mut guesser = Guesser::new(Vec::new());
+    // one turn isn't enough unless the opener happens to be the answer
+    let fates: Word = "FATES".parse().unwrap();
+    let record = play_game(&mut guesser, fates, 1);
+    assert!(!record.success, "FATES shouldn't be the opener: {:?}", record.guesses);
+    assert_eq!(loss_report(&guesser, fates, false), "the word was FATES");
+    assert_eq!(loss_report(&guesser, fates, true), "the word was FATES\nFATES was still a candidate, the strategy ran out of turns");
+
+    let guesser = Guesser::new_with_lists(&ANSWERS[..100], &ALLOWED_GUESSES);
+    let missing = *ANSWERS.last().unwrap();
+    assert!(loss_report(&guesser, missing, true).ends_with("was not a candidate, it may be missing from the answer list"));
+  }
+
+  #[test]
+  fn test_play_boards() {
+    init_options();
+    let answers: Vec<Word> = ["CRANE", "MOIST"].iter().map(|s| s.parse().unwrap()).collect();
+    let mut guessers = vec![Guesser::new(Vec::new()), Guesser::new(Vec::new())];
+    let (guesses, solved) = play_boards(&mut guessers, &answers, 7);
+    for (answer, solved_on) in answers.iter().zip(&solved) {
+      let n = solved_on.unwrap_or_else(|| panic!("{answer} should be solved in 7 turns: {guesses:?}"));
+      assert_eq!(guesses[n as usize - 1], *answer);
+    }
+    assert_eq!(guesses.len() as u32, solved.iter().flatten().copied().max().unwrap());
+
+    // one board is just a normal game
+    let mut guessers = vec![Guesser::new(Vec::new())];
+    let (guesses, solved) = play_boards(&mut guessers, &answers[..1], 6);
+    let record = play_game(&mut Guesser::new(Vec::new()), answers[0], 6);
+    assert_eq!(guesses, record.guesses);
+    assert_eq!(solved, [record.turns()]);
+  }
+
+  #[test]
+  fn test_resume_stats() {
+    init_options();
+    let answers = &ANSWERS[..30];
+    let path = std::env::temp_dir().join(format!("wordle-helper-checkpoint-{}.partial", std::process::id()));
+    let uninterrupted = play_stats(answers, 6, DEFAULT_BATCH_SIZE, None, false).unwrap();
+
+    // interrupted after 12 games, partway through writing the 13th
+    play_stats(&answers[..13], 6, NonZeroUsize::new(5).unwrap(), Some(&path), false).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, &text[..text.trim_end().len() - 3]).unwrap();
+    let (_, games) = text.split_once('\n').unwrap();
+    assert_eq!(wordle_helper::stats::read_checkpoint(&games[..games.trim_end().len() - 3]).unwrap().len(), 12);
+
+    let resumed = play_stats(answers, 6, NonZeroUsize::new(7).unwrap(), Some(&path), true).unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(resumed, uninterrupted);
+    assert_eq!(StatsReport::new(6, resumed).summary(), StatsReport::new(6, uninterrupted).summary());
+    assert_eq!(saved.lines().count(), 31);
+  }
+
+  #[test]
+  fn test_resume_different_options() {
+    init_options();
+    let answers = &ANSWERS[..10];
+    let path = std::env::temp_dir().join(format!("wordle-helper-options-{}.partial", std::process::id()));
+    let uninterrupted = play_stats(answers, 6, DEFAULT_BATCH_SIZE, None, false).unwrap();
+
+    // every game was lost in the checkpoint's one turn, and none of them should count under six
+    play_stats(answers, 1, DEFAULT_BATCH_SIZE, Some(&path), false).unwrap();
+    let resumed = play_stats(answers, 6, DEFAULT_BATCH_SIZE, Some(&path), true).unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(resumed, uninterrupted);
+    assert!(saved.starts_with("# turns=6 "), "{saved}");
+
+    // same turns, but a different word list, language or prior
+    let variants = [
+      AppOptions { language: Language::Spanish, ..default_options() },
+      AppOptions { dictionary: Some("words.txt".into()), ..default_options() },
+      AppOptions { answers: Some("answers.txt".into()), ..default_options() },
+      AppOptions { prior: Some("freq.tsv".into()), ..default_options() },
+    ];
+    for variant in &variants {
+      let header = checkpoint_header(variant, 6);
+      assert_ne!(header, checkpoint_header(&default_options(), 6));
+      play_stats(answers, 1, DEFAULT_BATCH_SIZE, Some(&path), false).unwrap();
+      let lost = std::fs::read_to_string(&path).unwrap();
+      let (_, games) = lost.split_once('\n').unwrap();
+      std::fs::write(&path, format!("{header}\n{games}")).unwrap();
+      let resumed = play_stats(answers, 6, DEFAULT_BATCH_SIZE, Some(&path), true).unwrap();
+      std::fs::remove_file(&path).unwrap();
+      assert_eq!(resumed, uninterrupted, "reused games saved with {header}");
+    }
+  }
+
+  #[test]
+  fn test_unlimited_turns() {
+    init_options();
+    // each guess only rules out itself, so between them the answers take every number of turns up to 8
+    let words: &'static [Word] = ["BATES", "DATES", "FATES", "GATES", "HATES", "MATES", "RATES", "SATES"]
+      .map(|s| s.parse().unwrap()).to_vec().leak();
+    let mut guesser = Guesser::new_with_lists(words, words);
+    guesser.set_max_turns(UNLIMITED_TURNS);
+    let mut turns = Vec::new();
+    for &answer in words {
+      let record = play_game(&mut guesser, answer, UNLIMITED_TURNS);
+      assert!(record.success, "{answer} wasn't solved: {:?}", record.guesses);
+      assert_eq!(record.remaining.len(), record.guesses.len());
+      assert_eq!(record.remaining[0], words.len());
+      assert!(record.remaining.is_sorted_by(|a, b| a >= b), "candidates only ever shrink: {:?}", record.remaining);
+      turns.push((record.guesses.len(), answer));
+    }
+    turns.sort_unstable();
+    assert_eq!(turns.iter().map(|&(n, _)| n).collect::<Vec<_>>(), (1..=words.len()).collect::<Vec<_>>());
+    // the same game is lost when capped at the usual six
+    let (_, slowest) = turns[words.len() - 1];
+    assert!(!play_game(&mut guesser, slowest, 6).success);
+
+    // stops once there's nothing left to guess instead of looping forever
+    let mut guesser = Guesser::new_with_lists(&ANSWERS[..100], &ALLOWED_GUESSES);
+    let missing = *ANSWERS.last().unwrap();
+    let record = play_game(&mut guesser, missing, UNLIMITED_TURNS);
+    assert!(!record.success);
+    assert!(record.guesses.len() <= 100);
+  }
+
   #[test]
   fn test_random() {
-    let mut rng = rng();
-    let mut candidates_buf = Some(Vec::new());
+    init_options();
+    let mut rng = seeded_rng();
+    let mut guesser = Guesser::new(Vec::new());
     let mut final_boards = Vec::new();
-    'rounds: for (round, word) in FIVE_LETTER_WORDS.choose_multiple(&mut rng, 10).enumerate() {
+    'rounds: for (round, word) in ANSWERS.choose_multiple(&mut rng, 10).enumerate() {
       println!("\nround {round}:");
-      let mut guesser = Guesser::new(candidates_buf.take().expect("should always have buffer at round start"));
+      guesser.reset();
       let mut guesses = Vec::new();
       let mut attempts = Attempts::new();
       for turn in 1..=6 {
@@ -419,16 +2004,15 @@ mod tests {
         if guess == word {
           println!("won on turn {turn}");
           final_boards.push((round, word, attempts, guesses));
-          candidates_buf = Some(guesser.extract_resources());
           continue 'rounds;
         }
-        guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i])));
+        guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i]))).unwrap();
         guesser.prune(turn);
+        assert!(guesser.is_consistent(*word), "the actual word should always fit the feedback");
         assert!(guesser.candidates().contains(word), "should never remove actual word from candidates");
       }
       println!("failed to identify word in alloted time :(");
       final_boards.push((round, word, attempts, guesses));
-      candidates_buf = Some(guesser.extract_resources());
     }
     for (round, word, board, guesses) in final_boards.into_iter() {
       println!("round {round}: {word}\n{board}");
@@ -438,4 +2022,91 @@ mod tests {
       println!();
     }
   }
+
+  /// What went wrong replaying the feedback `guesses` got for `word`, if anything did
+  ///
+  /// Either the feedback was called contradictory, or a word that still fit it was pruned.
+  fn prune_failure(word: Word, guesses: &[Word]) -> Option<String> {
+    let mut guesser = Guesser::new(Vec::new());
+    let mut before = guesser.candidates().to_vec();
+    for (turn, &guess) in (1..).zip(guesses) {
+      let stats = check_word(word, guess);
+      if let Err(e) = guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i]))) {
+        return Some(format!("the feedback for {guess} was called contradictory: {e}"));
+      }
+      guesser.prune(turn);
+      let played = &guesses[..turn as usize];
+      let fits = |other: Word| played.iter().all(|&guess| check_word(other, guess) == check_word(word, guess));
+      if let Some(lost) = before.iter().copied().find(|&other| fits(other) && !guesser.candidates().contains(&other)) {
+        return Some(format!("{lost} fit the feedback but was pruned"));
+      }
+      before = guesser.candidates().to_vec();
+    }
+    None
+  }
+
+  #[test]
+  fn test_prune_keeps_consistent_words() {
+    init_options();
+    let mut rng = seeded_rng();
+    for _ in 0..200 {
+      // each case gets its own seed, so it doesn't depend on how many words earlier cases drew
+      let case_seed: u64 = rng.random();
+      let mut case_rng = StdRng::seed_from_u64(case_seed);
+      let word = *ANSWERS.choose(&mut case_rng).unwrap();
+      let guesses: Vec<Word> = (0..case_rng.random_range(1..=5))
+        .map(|_| *ALLOWED_GUESSES.choose(&mut case_rng).unwrap())
+        .collect();
+      if prune_failure(word, &guesses).is_none() {
+        continue;
+      }
+      // drop every guess that isn't needed to reproduce it
+      let mut minimal = guesses;
+      let mut i = 0;
+      while i < minimal.len() {
+        let mut fewer = minimal.clone();
+        fewer.remove(i);
+        if prune_failure(word, &fewer).is_some() {
+          minimal = fewer;
+        } else {
+          i += 1;
+        }
+      }
+      let failure = prune_failure(word, &minimal).unwrap();
+      let played = minimal.iter().map(Word::to_string).collect::<Vec<_>>().join(" ");
+      panic!("case {case_seed}: solving {word} after {played}, {failure}");
+    }
+  }
+
+  #[test]
+  fn test_hardmode() {
+    init_options();
+    let mut rng = seeded_rng();
+    let mut guesser = Guesser::new(Vec::new());
+    guesser.set_hardmode(true);
+    for word in ANSWERS.choose_multiple(&mut rng, 20) {
+      guesser.reset();
+      let mut history = Vec::new();
+      for turn in 1..=6 {
+        let guess = *guesser.guess().expect("should always have a suggestion");
+        for (prev, stats) in &history {
+          let (prev, stats): (&Word, &WordFeedback) = (prev, stats);
+          for (i, (ch, stat)) in prev.iter().zip(stats.iter()).enumerate() {
+            match stat {
+              LetterFeedback::Confirmed => assert_eq!(guess[i], *ch, "{guess} dropped confirmed '{ch}' from {prev} (solving {word})"),
+              LetterFeedback::Required => assert!(guess.contains(ch) && guess[i] != *ch, "{guess} misused required '{ch}' from {prev} (solving {word})"),
+              LetterFeedback::Excluded => {}
+            }
+          }
+        }
+        if &guess == word {
+          break;
+        }
+        let stats = check_word(*word, guess);
+        history.push((guess, stats));
+        guesser.analyze(std::array::from_fn(|i| (guess[i], stats[i]))).unwrap();
+        guesser.prune(turn);
+      }
+    }
+  }
 }
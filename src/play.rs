@@ -1,11 +1,67 @@
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use crate::{guess::{LetterFeedback, WordFeedback}, word::Word};
+use std::num::NonZeroUsize;
+#[cfg(feature = "parallel")]
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use crate::{guess::{DenseFeedbackMap, LetterFeedback, WordFeedback}, word::Word};
+
+pub fn check_word<const N: usize>(word: Word<N>, guess: Word<N>) -> WordFeedback<N> {
+  let mut feedback = [LetterFeedback::Excluded; N];
+
+  // letters of the word not accounted for by an exact match
+  let mut remaining = [0u8; 26];
+  for (i, (w, g)) in word.iter().zip(guess.iter()).enumerate() {
+    if w == g {
+      feedback[i] = LetterFeedback::Confirmed;
+    } else {
+      remaining[w.index()] += 1;
+    }
+  }
+
+  // each unmatched letter in the word can only account for one misplaced letter in the guess
+  for (g, fb) in guess.iter().zip(feedback.iter_mut()) {
+    if *fb != LetterFeedback::Confirmed && remaining[g.index()] > 0 {
+      remaining[g.index()] -= 1;
+      *fb = LetterFeedback::Required;
+    }
+  }
+
+  WordFeedback::new(feedback)
+}
+
+/// Same as [`check_word`], comparing every letter at once
+///
+/// Letter `i` of the guess is yellow if fewer of the guess's earlier unmatched copies of it
+/// come before it than there are unmatched copies in the word.
+/// Words can be at most eight letters long.
+#[cfg(feature = "simd")]
+pub fn check_word_simd<const N: usize>(word: Word<N>, guess: Word<N>) -> WordFeedback<N> {
+  use std::simd::prelude::*;
+  const { assert!(N <= 8, "words must fit in a u8x8") };
+
+  let pack = |w: Word<N>| {
+    let mut bytes = [0; 8];
+    bytes[..N].copy_from_slice(w.as_bytes());
+    u8x8::from_array(bytes)
+  };
+  let (w, g) = (pack(word), pack(guess));
+  let green = w.simd_eq(g);
+  let lane = u8x8::from_array([0, 1, 2, 3, 4, 5, 6, 7]);
+  let (one, zero) = (u8x8::splat(1), u8x8::splat(0));
+
+  // unmatched copies of each guess letter in the word, and unmatched copies earlier in the guess
+  let mut available = zero;
+  let mut used = zero;
+  for j in 0..N {
+    if !green.test(j) {
+      available += g.simd_eq(u8x8::splat(word[j].to_u8())).select(one, zero);
+      used += (g.simd_eq(u8x8::splat(guess[j].to_u8())) & lane.simd_gt(u8x8::splat(j as u8))).select(one, zero);
+    }
+  }
+  let yellow = !green & used.simd_lt(available);
 
-pub fn check_word(word: Word, guess: Word) -> WordFeedback {
   WordFeedback::new(std::array::from_fn(|i|
-    if word.0[i] == guess.0[i] {
+    if green.test(i) {
       LetterFeedback::Confirmed
-    } else if word.0.contains(&guess.0[i]) {
+    } else if yellow.test(i) {
       LetterFeedback::Required
     } else {
       LetterFeedback::Excluded
@@ -13,12 +69,348 @@ pub fn check_word(word: Word, guess: Word) -> WordFeedback {
   ))
 }
 
-pub fn grade_many(guesses: &[Word], words: &[Word]) -> rayon::iter::Map<rayon::range::Iter<usize>, impl Fn(usize) -> (Word, Word, WordFeedback)> {
+/// Fastest available [`check_word`]
+#[inline]
+fn grade<const N: usize>(word: Word<N>, guess: Word<N>) -> WordFeedback<N> {
+  #[cfg(feature = "simd")]
+  return check_word_simd(word, guess);
+  #[cfg(not(feature = "simd"))]
+  return check_word(word, guess);
+}
+
+fn grade_pair<const N: usize>(guesses: &[Word<N>], words: &[Word<N>]) -> impl Fn(usize) -> (Word<N>, Word<N>, WordFeedback<N>) {
   let words_len = words.len();
-  (0..guesses.len()*words_len)
+  move |i| {
+    let (guess, word) = (guesses[i / words_len], words[i % words_len]);
+    (guess, word, grade(word, guess))
+  }
+}
+
+/// Every guess graded against every word, as `(guess, word, grade)`, in the same order as [`grade_many`]
+#[cfg(feature = "parallel")]
+pub fn grade_many_iter<const N: usize>(guesses: &[Word<N>], words: &[Word<N>]) -> rayon::iter::Map<rayon::range::Iter<usize>, impl Fn(usize) -> (Word<N>, Word<N>, WordFeedback<N>)> {
+  (0..guesses.len()*words.len())
     .into_par_iter()
-    .map(move |i| {
-      let (guess, word) = (guesses[i / words_len], words[i % words_len]);
-      (guess, word, check_word(word, guess))
-    })
+    .map(grade_pair(guesses, words))
+}
+
+/// Every guess graded against every word, as `(guess, word, grade)`, in the same order as [`grade_many`]
+#[cfg(not(feature = "parallel"))]
+pub fn grade_many_iter<const N: usize>(guesses: &[Word<N>], words: &[Word<N>]) -> std::iter::Map<std::ops::Range<usize>, impl Fn(usize) -> (Word<N>, Word<N>, WordFeedback<N>)> {
+  (0..guesses.len()*words.len())
+    .map(grade_pair(guesses, words))
+}
+
+/// Grade every guess against every word into `buffer`, on the rayon pool if enabled
+///
+/// `buffer[i * words.len() + j]` is the grade of `guesses[i]` if `words[j]` were the answer.
+pub fn grade_many<const N: usize>(guesses: &[Word<N>], words: &[Word<N>], buffer: &mut [WordFeedback<N>]) -> Result<(), GradeError> {
+  check_sizes(guesses, words, buffer)?;
+  let grade_pair = grade_pair(guesses, words);
+  #[cfg(feature = "parallel")]
+  buffer.par_iter_mut().enumerate().for_each(|(i, out)| *out = grade_pair(i).2);
+  #[cfg(not(feature = "parallel"))]
+  buffer.iter_mut().enumerate().for_each(|(i, out)| *out = grade_pair(i).2);
+  Ok(())
+}
+
+/// Inputs [`grade_many`] can't grade
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GradeError {
+  /// There were no guesses to grade
+  NoGuesses,
+  /// There were no words to grade the guesses against
+  NoWords,
+  /// The buffer didn't have room for exactly one grade per pair of guess and word
+  BufferSize { expected: usize, found: usize },
+}
+
+impl std::fmt::Display for GradeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::NoGuesses => write!(f, "no guesses to grade"),
+      Self::NoWords => write!(f, "no words to grade against"),
+      Self::BufferSize { expected, found } => write!(f, "buffer should hold {expected} grades, but holds {found}"),
+    }
+  }
+}
+
+impl std::error::Error for GradeError {}
+
+/// Grade every guess against every word into `buffer`, split across `threads` threads
+///
+/// `buffer[i * words.len() + j]` is the grade of `guesses[i]` if `words[j]` were the answer.
+pub fn grade_many_with_threads<const N: usize>(guesses: &[Word<N>], words: &[Word<N>], buffer: &mut [WordFeedback<N>], threads: NonZeroUsize) -> Result<(), GradeError> {
+  check_sizes(guesses, words, buffer)?;
+  grade_many_with_threads_unchecked(guesses, words, buffer, threads);
+  Ok(())
+}
+
+fn check_sizes<const N: usize>(guesses: &[Word<N>], words: &[Word<N>], buffer: &[WordFeedback<N>]) -> Result<(), GradeError> {
+  if guesses.is_empty() {
+    return Err(GradeError::NoGuesses);
+  }
+  if words.is_empty() {
+    return Err(GradeError::NoWords);
+  }
+  let expected = guesses.len()*words.len();
+  if buffer.len() != expected {
+    return Err(GradeError::BufferSize { expected, found: buffer.len() });
+  }
+  Ok(())
+}
+
+/// [`grade_many_with_threads`] without validating the inputs first
+///
+/// # Panics
+///
+/// If `words` is empty, or if `buffer` is longer than `guesses.len() * words.len()`.
+/// Grades are left out if it's shorter.
+pub(crate) fn grade_many_with_threads_unchecked<const N: usize>(guesses: &[Word<N>], words: &[Word<N>], buffer: &mut [WordFeedback<N>], threads: NonZeroUsize) {
+  fill_with_threads(buffer, threads, |i| grade(words[i % words.len()], guesses[i / words.len()]));
+}
+
+/// Set `buffer[i] = f(i)` for every `i`, split across `threads` threads
+///
+/// With the `wasm` feature, everything is done on the calling thread.
+fn fill_with_threads<T: Send>(buffer: &mut [T], threads: NonZeroUsize, f: impl Fn(usize) -> T + Sync) {
+  let fill = |offset: usize, chunk: &mut [T]| {
+    for (i, item) in (offset..).zip(chunk.iter_mut()) {
+      *item = f(i);
+    }
+  };
+  if cfg!(feature = "wasm") || threads.get() == 1 {
+    fill(0, buffer);
+  } else {
+    let chunk_size = buffer.len().div_ceil(threads.get()).max(1);
+    std::thread::scope(|s| {
+      for (k, chunk) in buffer.chunks_mut(chunk_size).enumerate() {
+        let fill = &fill;
+        s.spawn(move || fill(k*chunk_size, chunk));
+      }
+    });
+  }
+}
+
+/// Every guess graded against every answer ahead of time
+///
+/// Each grade is stored as its [`WordFeedback::to_index`] in a single byte, so words can be at most five letters long.
+pub struct FeedbackMatrix<const N: usize = 5> {
+  guesses: &'static [Word<N>],
+  /// Each answer with its column, sorted alphabetically
+  columns: Vec<(Word<N>, u32)>,
+  answers_len: usize,
+  grades: Vec<u8>,
+}
+
+impl<const N: usize> FeedbackMatrix<N> {
+  pub fn new(guesses: &'static [Word<N>], answers: &'static [Word<N>], threads: NonZeroUsize) -> Self {
+    const { assert!(WordFeedback::<N>::COMBINATIONS <= 256, "grades must fit in a byte") };
+    let mut grades = vec![0; guesses.len()*answers.len()];
+    fill_with_threads(&mut grades, threads, |i| {
+      grade(answers[i % answers.len()], guesses[i / answers.len()]).to_index() as u8
+    });
+    let mut columns: Vec<_> = answers.iter().copied().zip(0..).collect();
+    columns.sort_unstable();
+    Self { guesses, columns, answers_len: answers.len(), grades }
+  }
+
+  /// The guesses the rows were graded for, in order
+  pub fn guesses(&self) -> &'static [Word<N>] {
+    self.guesses
+  }
+
+  /// Column of `answer`, if it was one of the answers graded against
+  pub fn column(&self, answer: &Word<N>) -> Option<usize> {
+    self.columns.binary_search_by_key(answer, |(w, _)| *w).ok()
+      .map(|i| self.columns[i].1 as usize)
+  }
+
+  /// Grade of the guess at `row` against the answer at `column`
+  pub fn get(&self, row: usize, column: usize) -> WordFeedback<N> {
+    WordFeedback::from_index(self.grades[row*self.answers_len + column].into())
+      .expect("grades should be valid indices")
+  }
+}
+
+/// A host that never commits to an answer, like Absurdle
+///
+/// Every guess gets whichever feedback keeps the most answers possible.
+#[derive(Debug, Clone)]
+pub struct Absurdle<const N: usize = 5> {
+  remaining: Vec<Word<N>>,
+}
+
+impl<const N: usize> Absurdle<N> {
+  pub const fn new(answers: Vec<Word<N>>) -> Self {
+    Self { remaining: answers }
+  }
+
+  /// Answers that are still consistent with every feedback given
+  pub fn remaining(&self) -> &[Word<N>] {
+    &self.remaining
+  }
+
+  /// Feedback for `guess` from the biggest bucket of remaining answers
+  ///
+  /// Ties go to the bucket with fewer letters in the word, then fewer greens, then the lowest [`WordFeedback::to_index`].
+  pub fn respond(&mut self, guess: Word<N>) -> WordFeedback<N> {
+    use std::cmp::Reverse;
+    let mut sizes = DenseFeedbackMap::new();
+    for &word in &self.remaining {
+      *sizes.get_or_insert_with(check_word(word, guess), || 0) += 1;
+    }
+    let count = |feedback: WordFeedback<N>, of: LetterFeedback| feedback.iter().filter(|&&fb| fb >= of).count();
+    let feedback = sizes.entries()
+      .max_by_key(|&(feedback, &size)| (
+        size,
+        Reverse(count(feedback, LetterFeedback::Required)),
+        Reverse(count(feedback, LetterFeedback::Confirmed)),
+        Reverse(feedback.to_index()),
+      ))
+      .map_or(WordFeedback::new([LetterFeedback::Excluded; N]), |(feedback, _)| feedback);
+    self.remaining.retain(|&word| check_word(word, guess) == feedback);
+    feedback
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  extern crate test;
+  use super::*;
+  use LetterFeedback::{Confirmed as G, Required as Y, Excluded as B};
+
+  fn word(s: &[u8; 5]) -> Word {
+    Word::from_bytes(*s).unwrap()
+  }
+
+  #[test]
+  fn test_repeated_guess_letter() {
+    // only one L in ANGLE, so only the first unmatched L is yellow
+    assert_eq!(check_word(word(b"ANGLE"), word(b"ALLEY")), WordFeedback::new([G, Y, B, Y, B]));
+  }
+
+  #[test]
+  fn test_double_guess_single_answer() {
+    assert_eq!(check_word(word(b"ABIDE"), word(b"SPEED")), WordFeedback::new([B, B, Y, B, Y]));
+    // the exact match takes priority over the earlier misplaced copy
+    assert_eq!(check_word(word(b"AROMA"), word(b"LLAMA")), WordFeedback::new([B, B, Y, G, G]));
+    assert_eq!(check_word(word(b"CRANE"), word(b"EERIE")), WordFeedback::new([B, B, Y, B, G]));
+  }
+
+  #[test]
+  fn test_answer_more_copies_than_guess() {
+    assert_eq!(check_word(word(b"EERIE"), word(b"ENTER")), WordFeedback::new([G, B, B, Y, Y]));
+    assert_eq!(check_word(word(b"EERIE"), word(b"THEME")), WordFeedback::new([B, B, Y, B, G]));
+  }
+
+  #[test]
+  fn test_other_lengths() {
+    let planet = Word::from_bytes(*b"PLANET").unwrap();
+    let pallet = Word::from_bytes(*b"PALLET").unwrap();
+    assert_eq!(check_word(planet, pallet), WordFeedback::new([G, Y, Y, B, G, G]));
+    let tree = Word::from_bytes(*b"TREE").unwrap();
+    assert_eq!(check_word(tree, tree), WordFeedback::new([G; 4]));
+  }
+
+  #[test]
+  fn test_absurdle() {
+    let mut host = Absurdle::new(vec![word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH"), word(b"CRANE")]);
+    // the four -ATCH words share a bucket, so CRANE is given up
+    assert_eq!(host.respond(word(b"CRANE")), WordFeedback::new([Y, B, Y, B, B]));
+    assert_eq!(host.remaining(), [word(b"BATCH"), word(b"HATCH"), word(b"LATCH"), word(b"MATCH")]);
+    assert_eq!(host.respond(word(b"BLAHS")), WordFeedback::new([B, B, Y, Y, B]));
+    assert_eq!(host.remaining(), [word(b"HATCH"), word(b"MATCH")]);
+    // both are their own bucket, so it picks the one with fewer hints
+    assert_eq!(host.respond(word(b"HATCH")), WordFeedback::new([B, G, G, G, G]));
+    assert_eq!(host.respond(word(b"MATCH")), WordFeedback::new([G; 5]));
+    assert_eq!(host.remaining(), [word(b"MATCH")]);
+  }
+
+  #[test]
+  fn test_absurdle_ties() {
+    // one green gives away fewer letters than two yellows, even though it comes later in feedback order
+    let mut host = Absurdle::new(vec![word(b"BAFGH"), word(b"FGHIE")]);
+    assert_eq!(host.respond(word(b"ABCDE")), WordFeedback::new([B, B, B, B, G]));
+    assert_eq!(host.remaining(), [word(b"FGHIE")]);
+    // a yellow gives away as many letters as a green, but less about where
+    let mut host = Absurdle::new(vec![word(b"AFGHI"), word(b"FGHIA")]);
+    assert_eq!(host.respond(word(b"ABCDE")), WordFeedback::new([Y, B, B, B, B]));
+    // the same hints either way, so the lower index wins
+    let mut host = Absurdle::new(vec![word(b"EFGHI"), word(b"FGHIA")]);
+    assert_eq!(host.respond(word(b"ABCDE")), WordFeedback::new([Y, B, B, B, B]));
+  }
+
+  #[test]
+  fn test_grade_many_with_threads() {
+    let guesses = [word(b"CRANE"), word(b"SLATE"), word(b"EERIE")];
+    let words = [word(b"ABIDE"), word(b"ANGLE"), word(b"AROMA"), word(b"CRANE")];
+    let expected: Vec<_> = guesses.iter()
+      .flat_map(|&g| words.iter().map(move |&w| check_word(w, g)))
+      .collect();
+    for threads in [1, 2, 5, 64] {
+      let mut buffer = vec![WordFeedback::new([B; 5]); guesses.len()*words.len()];
+      grade_many_with_threads(&guesses, &words, &mut buffer, NonZeroUsize::new(threads).unwrap()).unwrap();
+      assert_eq!(buffer, expected, "{threads} threads");
+    }
+  }
+
+  #[test]
+  fn test_grade_many() {
+    let guesses = [word(b"CRANE"), word(b"SLATE"), word(b"EERIE")];
+    let words = [word(b"ABIDE"), word(b"ANGLE"), word(b"AROMA"), word(b"CRANE")];
+    let expected: Vec<_> = guesses.iter()
+      .flat_map(|&g| words.iter().map(move |&w| check_word(w, g)))
+      .collect();
+    let mut buffer = vec![WordFeedback::new([B; 5]); guesses.len()*words.len()];
+    grade_many(&guesses, &words, &mut buffer).unwrap();
+    assert_eq!(buffer, expected);
+    assert_eq!(grade_many(&guesses, &words, &mut buffer[1..]), Err(GradeError::BufferSize { expected: 12, found: 11 }));
+  }
+
+  #[test]
+  fn test_grade_many_iter() {
+    let guesses = [word(b"CRANE"), word(b"SLATE")];
+    let words = [word(b"ABIDE"), word(b"CRANE"), word(b"STALE")];
+    let graded: Vec<_> = grade_many_iter(&guesses, &words).collect();
+    let expected: Vec<_> = guesses.iter()
+      .flat_map(|&g| words.iter().map(move |&w| (g, w, check_word(w, g))))
+      .collect();
+    assert_eq!(graded, expected);
+  }
+
+  #[test]
+  fn test_grade_errors() {
+    let guesses = [word(b"CRANE"), word(b"SLATE")];
+    let words = [word(b"ABIDE"), word(b"ANGLE"), word(b"AROMA")];
+    let mut buffer = vec![WordFeedback::new([B; 5]); 5];
+    assert_eq!(grade_many_with_threads(&guesses, &words, &mut buffer, NonZeroUsize::MIN), Err(GradeError::BufferSize { expected: 6, found: 5 }));
+    assert_eq!(grade_many_with_threads(&[], &words, &mut [], NonZeroUsize::MIN), Err(GradeError::NoGuesses));
+    assert_eq!(grade_many_with_threads(&guesses, &[], &mut [], NonZeroUsize::MIN), Err(GradeError::NoWords));
+  }
+
+  #[cfg(feature = "simd")]
+  #[test]
+  fn test_simd_matches_scalar() {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    let mut rng = StdRng::seed_from_u64(5);
+    // a small alphabet makes repeated letters common
+    for alphabet in [b"ABCDEFGHIJKLMNOPQRSTUVWXYZ".as_slice(), b"ABCDE", b"EEL"] {
+      for _ in 0..2000 {
+        let mut random_word = || Word::<5>::from_bytes(std::array::from_fn(|_| alphabet[rng.random_range(0..alphabet.len())])).unwrap();
+        let (w, g) = (random_word(), random_word());
+        assert_eq!(check_word_simd(w, g), check_word(w, g), "grading {g} against {w}");
+      }
+    }
+  }
+
+  #[bench]
+  fn grade_on_the_fly(b: &mut test::Bencher) {
+    let guesses = &crate::dictionary::ALLOWED_GUESSES[..1024];
+    let words = &crate::dictionary::ANSWERS[..64];
+    let mut buffer = vec![WordFeedback::new([LetterFeedback::Excluded; 5]); guesses.len()*words.len()];
+    b.iter(|| {
+      grade_many_with_threads_unchecked(test::black_box(guesses), test::black_box(words), &mut buffer, NonZeroUsize::MIN);
+    });
+  }
 }
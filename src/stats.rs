@@ -0,0 +1,511 @@
+use std::{borrow::Cow, io::{self, Write}};
+use crate::{guess::{FeedbackSymbols, LetterFeedback, WordFeedback}, word::Word};
+
+/// File format for the results of a stats run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsFormat {
+  #[default]
+  Tsv,
+  Json,
+  Csv,
+}
+
+impl StatsFormat {
+  pub const fn file_name(self) -> &'static str {
+    match self {
+      Self::Tsv => "stats.tsv",
+      Self::Json => "stats.json",
+      Self::Csv => "stats.csv",
+    }
+  }
+}
+
+impl std::str::FromStr for StatsFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "tsv" => Ok(Self::Tsv),
+      "json" => Ok(Self::Json),
+      "csv" => Ok(Self::Csv),
+      _ => Err(format!("unknown format `{s}`, expected one of: tsv, json, csv")),
+    }
+  }
+}
+
+/// One solved (or failed) answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+  pub word: Word,
+  pub success: bool,
+  /// Every word played, in order
+  pub guesses: Vec<Word>,
+  /// How many of the guesses were burners, which couldn't have been the answer
+  pub burners: u32,
+  /// How many candidates were left when each guess was played, or empty if that wasn't recorded
+  pub remaining: Vec<usize>,
+}
+
+impl GameRecord {
+  /// Number of turns taken to win, [`None`] if the game was lost
+  pub fn turns(&self) -> Option<u32> {
+    self.success.then_some(self.guesses.len() as u32)
+  }
+}
+
+/// Written as one line, e.g. `CRANE won 0 SLATE:2315 CRANE:4`, where `0` is the number of burners
+/// and each guess is followed by how many candidates were left when it was played, if that was recorded
+impl std::fmt::Display for GameRecord {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{} {} {}", self.word, if self.success { "won" } else { "lost" }, self.burners)?;
+    for (i, guess) in self.guesses.iter().enumerate() {
+      write!(f, " {guess}")?;
+      if let Some(n) = self.remaining.get(i) {
+        write!(f, ":{n}")?;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl std::str::FromStr for GameRecord {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parts = s.split_whitespace();
+    let word = parts.next().ok_or("empty game")?;
+    let word = word.parse().map_err(|e| format!("bad answer `{word}`: {e}"))?;
+    let success = match parts.next() {
+      Some("won") => true,
+      Some("lost") => false,
+      _ => return Err("expected `won` or `lost` after the answer".to_string()),
+    };
+    let burners = parts.next().ok_or("missing number of burners")?;
+    let burners = burners.parse().map_err(|e| format!("bad number of burners `{burners}`: {e}"))?;
+    let mut guesses = Vec::new();
+    let mut remaining = Vec::new();
+    for part in parts {
+      let (guess, count) = part.split_once(':').map_or((part, None), |(guess, count)| (guess, Some(count)));
+      guesses.push(guess.parse().map_err(|e| format!("bad guess `{guess}`: {e}"))?);
+      if let Some(count) = count {
+        remaining.push(count.parse().map_err(|e| format!("bad candidate count `{count}`: {e}"))?);
+      }
+    }
+    // written by a version that didn't record them
+    if !remaining.is_empty() && remaining.len() != guesses.len() {
+      return Err("only some guesses have a candidate count".to_string());
+    }
+    Ok(Self { word, success, guesses, burners, remaining })
+  }
+}
+
+/// Where a stats run saves each batch of finished games, so `--resume` can pick up where it left off
+pub const CHECKPOINT_FILE: &str = "stats.partial";
+
+/// Every game saved to a checkpoint, one [`GameRecord`] per line
+///
+/// A last line without a newline was cut off partway through writing, so it's left out.
+pub fn read_checkpoint(text: &str) -> Result<Vec<GameRecord>, String> {
+  text.split_inclusive('\n')
+    .enumerate()
+    .filter(|(_, line)| line.ends_with('\n') && !line.trim().is_empty())
+    .map(|(n, line)| line.parse().map_err(|e| format!("line {}: {e}", n + 1)))
+    .collect()
+}
+
+/// The plain-text symbols game logs are written and read with, whatever symbols the game was played with
+const LOG_SYMBOLS: FeedbackSymbols = FeedbackSymbols {
+  input: FeedbackSymbols::DEFAULT.input,
+  output: FeedbackSymbols::DEFAULT.input,
+};
+
+/// Every word played in a game and the feedback it got
+///
+/// Written as one line, e.g. `CRANE SLATE:__?_+ CRANE:+++++`.
+/// The answer is `?` if the game was lost without learning it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayedGame<const N: usize = 5> {
+  pub answer: Option<Word<N>>,
+  pub turns: Vec<(Word<N>, WordFeedback<N>)>,
+}
+
+impl<const N: usize> PlayedGame<N> {
+  pub fn is_won(&self) -> bool {
+    self.turns.last().is_some_and(|(_, fb)| fb.iter().all(|&x| x == LetterFeedback::Confirmed))
+  }
+
+  /// Append this game to the log at `path`, creating it if needed
+  pub fn append_to(&self, path: &std::path::Path) -> io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{self}")
+  }
+}
+
+impl<const N: usize> std::fmt::Display for PlayedGame<N> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.answer {
+      Some(answer) => answer.fmt(f)?,
+      None => '?'.fmt(f)?,
+    }
+    for (word, feedback) in &self.turns {
+      write!(f, " {word}:{}", LOG_SYMBOLS.display(*feedback))?;
+    }
+    Ok(())
+  }
+}
+
+impl<const N: usize> std::str::FromStr for PlayedGame<N> {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parts = s.split_whitespace();
+    let answer = match parts.next() {
+      None => return Err("empty game".to_string()),
+      Some("?") => None,
+      Some(word) => Some(word.parse().map_err(|e| format!("bad answer `{word}`: {e}"))?),
+    };
+    let turns = parts
+      .map(|turn| {
+        let (word, feedback) = turn.split_once(':').ok_or_else(|| format!("`{turn}` should be WORD:FEEDBACK"))?;
+        Ok((
+          word.parse().map_err(|e| format!("bad word `{word}`: {e}"))?,
+          LOG_SYMBOLS.parse(feedback).map_err(|e| format!("bad feedback `{feedback}`: {e}"))?,
+        ))
+      })
+      .collect::<Result<_, String>>()?;
+    Ok(Self { answer, turns })
+  }
+}
+
+/// Every game in a log written by [`PlayedGame::append_to`], skipping blank lines
+pub fn read_log(text: &str) -> Result<Vec<PlayedGame>, String> {
+  text.lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(n, line)| line.parse().map_err(|e| format!("line {}: {e}", n + 1)))
+    .collect()
+}
+
+/// Aggregate results over every game in a [`StatsReport`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+  pub won: usize,
+  pub lost: usize,
+  /// Quartiles and mean are only defined if at least one game was won
+  pub turns: Option<TurnStats>,
+  /// Wins on each turn, followed by the number of losses
+  pub distribution: Vec<usize>,
+  pub burners: BurnerStats,
+}
+
+/// How much the burners suggested by the tiebreaker were played, and how those games went
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BurnerStats {
+  /// Burners played over every game
+  pub turns: usize,
+  /// Games with at least one burner
+  pub games: usize,
+  /// Games with at least one burner that were won
+  pub won: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnStats {
+  pub min: u32,
+  pub max: u32,
+  pub mean: f64,
+  /// Population variance, the games won being every game there is to consider
+  pub variance: f64,
+  pub std_dev: f64,
+  /// Most common number of turns, the fewest on a tie
+  pub mode: u32,
+  pub q1: u32,
+  pub median: u32,
+  pub q3: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsReport {
+  pub max_turns: u32,
+  pub games: Vec<GameRecord>,
+}
+
+impl StatsReport {
+  pub const fn new(max_turns: u32, games: Vec<GameRecord>) -> Self {
+    Self { max_turns, games }
+  }
+
+  /// A game won in more than `max_turns` counts as lost
+  pub fn summary(&self) -> Summary {
+    let mut successes: Vec<u32> = self.games.iter()
+      .filter_map(|game| self.turns_if_won(game))
+      .collect();
+    successes.sort();
+
+    let won = successes.len();
+    let lost = self.games.len() - won;
+
+    let mut distribution = vec![0; self.max_turns as usize + 1];
+    for &t in &successes {
+      distribution[t as usize - 1] += 1;
+    }
+    distribution[self.max_turns as usize] = lost;
+
+    let turns = (!successes.is_empty()).then(|| {
+      let mean = successes.iter().copied().map(f64::from).sum::<f64>() / won as f64;
+      let variance = successes.iter().map(|&t| (f64::from(t) - mean).powi(2)).sum::<f64>() / won as f64;
+      // `successes` is sorted, so the first of the most common is the fewest turns
+      let mode = successes.chunk_by(|a, b| a == b)
+        .rev()
+        .max_by_key(|run| run.len())
+        .map_or(0, |run| run[0]);
+      TurnStats {
+        min: successes[0],
+        max: successes[won - 1],
+        mean,
+        variance,
+        std_dev: variance.sqrt(),
+        mode,
+        q1: successes[won / 4],
+        median: successes[2*won / 4],
+        q3: successes[3*won / 4],
+      }
+    });
+
+    let mut burners = BurnerStats::default();
+    for game in self.games.iter().filter(|game| game.burners > 0) {
+      burners.turns += game.burners as usize;
+      burners.games += 1;
+      burners.won += usize::from(self.turns_if_won(game).is_some());
+    }
+
+    Summary { won, lost, turns, distribution, burners }
+  }
+
+  fn turns_if_won(&self, game: &GameRecord) -> Option<u32> {
+    game.turns().filter(|&turns| turns <= self.max_turns)
+  }
+
+  pub fn write(&self, format: StatsFormat, writer: impl Write) -> io::Result<()> {
+    match format {
+      StatsFormat::Tsv => self.write_tsv(writer),
+      StatsFormat::Json => self.write_json(writer),
+      StatsFormat::Csv => self.write_csv(writer),
+    }
+  }
+
+  fn write_tsv(&self, mut writer: impl Write) -> io::Result<()> {
+    const FALSE: Word = Word::from_bytes(*b"FALSE").unwrap();
+    write!(writer, "\"Word\"\t\"Success\"\t\"Turns\"")?;
+    for turn in 1..=self.max_turns {
+      write!(writer, "\t\"Turn {turn} word\"")?;
+    }
+    for GameRecord { word, success, guesses, .. } in &self.games {
+      if *success {
+        write!(writer, "\n\"{}{word}\"\tTRUE\t{}", if word == &FALSE { "'" } else { "" }, guesses.len())?;
+      } else {
+        write!(writer, "\n\"{}{word}\"\tFALSE\t#N/A", if word == &FALSE { "'" } else { "" })?;
+      }
+      for guess in guesses {
+        write!(writer, "\t\"{}{guess}\"", if guess == &FALSE { "'" } else { "" })?;
+      }
+    }
+    writer.flush()
+  }
+
+  fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+    write!(writer, "Word,Success,Turns")?;
+    for turn in 1..=self.max_turns {
+      write!(writer, ",{}", csv_field(&format!("Turn {turn} word")))?;
+    }
+    write!(writer, "\r\n")?;
+    for game in &self.games {
+      write!(writer, "{}\r\n", csv_row(game))?;
+    }
+    writer.flush()
+  }
+
+  /// Words are plain ASCII letters, so nothing here needs escaping
+  fn write_json(&self, mut writer: impl Write) -> io::Result<()> {
+    let Summary { won, lost, turns, distribution, burners } = self.summary();
+    write!(writer, "{{\"max_turns\":{},\"games\":[", self.max_turns)?;
+    for (i, game) in self.games.iter().enumerate() {
+      if i > 0 { write!(writer, ",")?; }
+      write!(writer, "{{\"word\":\"{}\",\"success\":{},\"turns\":", game.word, game.success)?;
+      match game.turns() {
+        Some(n) => write!(writer, "{n}")?,
+        None => write!(writer, "null")?,
+      }
+      write!(writer, ",\"burners\":{},\"guesses\":[", game.burners)?;
+      for (j, guess) in game.guesses.iter().enumerate() {
+        if j > 0 { write!(writer, ",")?; }
+        write!(writer, "\"{guess}\"")?;
+      }
+      write!(writer, "],\"remaining\":[")?;
+      for (j, n) in game.remaining.iter().enumerate() {
+        if j > 0 { write!(writer, ",")?; }
+        write!(writer, "{n}")?;
+      }
+      write!(writer, "]}}")?;
+    }
+    write!(writer, "],\"summary\":{{\"won\":{won},\"lost\":{lost}")?;
+    if let Some(TurnStats { min, max, mean, variance, std_dev, mode, q1, median, q3 }) = turns {
+      write!(writer, ",\"min\":{min},\"max\":{max},\"mean\":{mean},\"variance\":{variance},\"std_dev\":{std_dev},\"mode\":{mode},\"q1\":{q1},\"median\":{median},\"q3\":{q3}")?;
+    }
+    write!(writer, ",\"burner_turns\":{},\"burner_games\":{},\"burner_games_won\":{}", burners.turns, burners.games, burners.won)?;
+    write!(writer, ",\"distribution\":[")?;
+    for (i, n) in distribution.iter().enumerate() {
+      if i > 0 { write!(writer, ",")?; }
+      write!(writer, "{n}")?;
+    }
+    write!(writer, "]}}}}")?;
+    writer.flush()
+  }
+}
+
+/// Quote a field per RFC 4180 if it contains a delimiter, quote, or line break
+pub fn csv_field(field: &str) -> Cow<'_, str> {
+  if field.contains([',', '"', '\r', '\n']) {
+    Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+  } else {
+    Cow::Borrowed(field)
+  }
+}
+
+/// A single game as a CSV record, without the line terminator
+///
+/// Lost games leave the turn count empty
+pub fn csv_row(game: &GameRecord) -> String {
+  let mut row = String::new();
+  row.push_str(&csv_field(game.word.as_str()));
+  row.push_str(if game.success { ",TRUE," } else { ",FALSE," });
+  if let Some(n) = game.turns() {
+    row.push_str(&n.to_string());
+  }
+  for guess in &game.guesses {
+    row.push(',');
+    row.push_str(&csv_field(guess.as_str()));
+  }
+  row
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn word(s: &str) -> Word {
+    s.parse().unwrap()
+  }
+
+  fn report() -> StatsReport {
+    StatsReport::new(3, vec![
+      GameRecord { word: word("CRANE"), success: true, guesses: vec![word("SLATE"), word("CRANE")], burners: 1, remaining: vec![2315, 4] },
+      GameRecord { word: word("FALSE"), success: true, guesses: vec![word("FALSE")], burners: 0, remaining: vec![2315] },
+      GameRecord { word: word("JAZZY"), success: false, guesses: vec![word("SLATE"), word("CRANE"), word("PIZZA")], burners: 2, remaining: Vec::new() },
+    ])
+  }
+
+  #[test]
+  fn test_summary() {
+    let summary = report().summary();
+    assert_eq!(summary.won, 2);
+    assert_eq!(summary.lost, 1);
+    assert_eq!(summary.distribution, [1, 1, 0, 1]);
+    let turns = summary.turns.unwrap();
+    assert_eq!((turns.min, turns.max, turns.mean), (1, 2, 1.5));
+    assert_eq!((turns.variance, turns.std_dev, turns.mode), (0.25, 0.5, 1));
+    assert_eq!(summary.burners, BurnerStats { turns: 3, games: 2, won: 1 });
+
+    let games = [4, 3, 4, 5, 3, 4, 6].map(|n| GameRecord { word: word("CRANE"), success: true, guesses: vec![word("SLATE"); n], burners: 0, remaining: Vec::new() });
+    let turns = StatsReport::new(6, games.to_vec()).summary().turns.unwrap();
+    assert_eq!(turns.mode, 4);
+    assert!((turns.variance - 48.0/49.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_summary_over_length() {
+    // e.g. resumed from a run with more turns
+    let games = [2, 8].map(|n| GameRecord { word: word("CRANE"), success: true, guesses: vec![word("SLATE"); n], burners: 1, remaining: Vec::new() });
+    let summary = StatsReport::new(6, games.to_vec()).summary();
+    assert_eq!((summary.won, summary.lost), (1, 1));
+    assert_eq!(summary.distribution, [0, 1, 0, 0, 0, 0, 1]);
+    assert_eq!(summary.burners.won, 1);
+  }
+
+  #[test]
+  fn test_write_json() {
+    let mut out = Vec::new();
+    report().write(StatsFormat::Json, &mut out).unwrap();
+    let json = String::from_utf8(out).unwrap();
+    assert!(serde_json::from_str::<serde_json::Value>(&json).is_ok(), "should be valid json");
+    assert!(json.starts_with("{\"max_turns\":3,\"games\":[{\"word\":\"CRANE\",\"success\":true,\"turns\":2,\"burners\":1,\"guesses\":[\"SLATE\",\"CRANE\"],\"remaining\":[2315,4]}"));
+    assert!(json.contains("{\"word\":\"JAZZY\",\"success\":false,\"turns\":null,"));
+    assert!(json.ends_with("\"summary\":{\"won\":2,\"lost\":1,\"min\":1,\"max\":2,\"mean\":1.5,\"variance\":0.25,\"std_dev\":0.5,\"mode\":1,\"q1\":1,\"median\":2,\"q3\":2,\"burner_turns\":3,\"burner_games\":2,\"burner_games_won\":1,\"distribution\":[1,1,0,1]}}"));
+  }
+
+  #[test]
+  fn test_csv_field() {
+    assert_eq!(csv_field("CRANE"), "CRANE");
+    assert_eq!(csv_field("FALSE"), "FALSE");
+    assert_eq!(csv_field("a,b"), "\"a,b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+  }
+
+  #[test]
+  fn test_csv_row() {
+    let [won, falsy, lost] = <[GameRecord; 3]>::try_from(report().games).unwrap();
+    assert_eq!(csv_row(&won), "CRANE,TRUE,2,SLATE,CRANE");
+    assert_eq!(csv_row(&falsy), "FALSE,TRUE,1,FALSE");
+    assert_eq!(csv_row(&lost), "JAZZY,FALSE,,SLATE,CRANE,PIZZA");
+
+    let mut out = Vec::new();
+    report().write(StatsFormat::Csv, &mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+    assert!(csv.starts_with("Word,Success,Turns,Turn 1 word,Turn 2 word,Turn 3 word\r\nCRANE,TRUE,2,"));
+    assert_eq!(csv.lines().count(), 4);
+  }
+
+  #[test]
+  fn test_checkpoint() {
+    let games = report().games;
+    assert_eq!(games[0].to_string(), "CRANE won 1 SLATE:2315 CRANE:4");
+    assert_eq!(games[2].to_string(), "JAZZY lost 2 SLATE CRANE PIZZA");
+    let text: String = games.iter().map(|game| format!("{game}\n")).collect();
+    assert_eq!(read_checkpoint(&text), Ok(games.clone()));
+    // interrupted while writing the last game
+    assert_eq!(read_checkpoint(&text[..text.len() - 4]), Ok(games[..2].to_vec()));
+    assert!(read_checkpoint("CRANE tied 0 SLATE\n").is_err());
+    assert!(read_checkpoint("CRANE won 0 SLAT\n").is_err());
+    assert!(read_checkpoint("CRANE won SLATE\n").is_err(), "missing burners");
+    assert!(read_checkpoint("CRANE won 0 SLATE:2315 CRANE\n").is_err(), "missing a count");
+    assert!(read_checkpoint("CRANE won 0 SLATE:many\n").is_err());
+  }
+
+  #[test]
+  fn test_game_log() {
+    let won = PlayedGame {
+      answer: Some(word("CRANE")),
+      turns: vec![(word("SLATE"), "__+_+".parse().unwrap()), (word("CRANE"), "+++++".parse().unwrap())],
+    };
+    let lost = PlayedGame {
+      answer: None,
+      turns: vec![(word("JAZZY"), "_?___".parse().unwrap())],
+    };
+    assert_eq!(won.to_string(), "CRANE SLATE:__+_+ CRANE:+++++");
+    assert_eq!(lost.to_string(), "? JAZZY:_?___");
+    assert!(won.is_won());
+    assert!(!lost.is_won());
+
+    let path = std::env::temp_dir().join(format!("wordle-helper-log-{}.txt", std::process::id()));
+    _ = std::fs::remove_file(&path);
+    won.append_to(&path).unwrap();
+    lost.append_to(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read_log(&text), Ok(vec![won, lost]));
+
+    assert!(read_log("CRANE SLATE\n").unwrap_err().starts_with("line 1:"));
+    assert!(read_log("\nCRANE SLATE:__+_\n").unwrap_err().starts_with("line 2:"));
+  }
+}
@@ -38,14 +38,29 @@ impl std::fmt::Display for Letter {
 }
 
 impl Letter {
+  /// A through Z
+  pub const ALL: [Letter; 26] = [
+    Letter::A, Letter::B, Letter::C, Letter::D, Letter::E, Letter::F, Letter::G, Letter::H, Letter::I,
+    Letter::J, Letter::K, Letter::L, Letter::M, Letter::N, Letter::O, Letter::P, Letter::Q, Letter::R,
+    Letter::S, Letter::T, Letter::U, Letter::V, Letter::W, Letter::X, Letter::Y, Letter::Z,
+  ];
+
+  /// Every letter, A through Z
+  pub fn iter() -> impl Iterator<Item = Letter> {
+    Self::ALL.into_iter()
+  }
+
   pub const fn from_u8(b: u8) -> Option<Self> {
-    if matches!(b, b'A'..=b'Z') {
+    if b.is_ascii_uppercase() {
       Some(unsafe { Self::from_u8_unchecked(b) })
     } else {
       None
     }
   }
 
+  /// # Safety
+  ///
+  /// `b` must be an ASCII uppercase letter
   pub const unsafe fn from_u8_unchecked(b: u8) -> Self {
     unsafe { std::mem::transmute(b) }
   }
@@ -66,60 +81,235 @@ impl Letter {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
-pub struct Word(pub [Letter; 5]);
+pub struct Word<const N: usize = 5>(pub [Letter; N]);
+
+/// The standard Wordle board
+pub type Word5 = Word<5>;
 
-impl std::ops::Deref for Word {
-  type Target = [Letter; 5];
+impl<const N: usize> std::ops::Deref for Word<N> {
+  type Target = [Letter; N];
 
   fn deref(&self) -> &Self::Target {
     &self.0
   }
 }
 
-impl std::ops::DerefMut for Word {
+impl<const N: usize> std::ops::DerefMut for Word<N> {
   fn deref_mut(&mut self) -> &mut Self::Target {
     &mut self.0
   }
 }
 
-impl Word {
-  pub const fn from_bytes(bytes: [u8; 5]) -> Option<Self> {
-    if matches!(bytes, [b'A'..=b'Z', b'A'..=b'Z', b'A'..=b'Z', b'A'..=b'Z', b'A'..=b'Z']) {
-      Some(unsafe { Self::from_bytes_unchecked(bytes) })
-    } else {
-      None
+impl<const N: usize> Word<N> {
+  pub const fn from_bytes(bytes: [u8; N]) -> Option<Self> {
+    let mut i = 0;
+    while i < N {
+      if !bytes[i].is_ascii_uppercase() {
+        return None;
+      }
+      i += 1;
     }
+    Some(unsafe { Self::from_bytes_unchecked(bytes) })
   }
 
-  pub const unsafe fn from_bytes_unchecked(bytes: [u8; 5]) -> Self {
-    unsafe { std::mem::transmute(bytes) }
+  /// # Safety
+  ///
+  /// Every byte must be an ASCII uppercase letter
+  pub const unsafe fn from_bytes_unchecked(bytes: [u8; N]) -> Self {
+    unsafe { Self(*(&raw const bytes).cast::<[Letter; N]>()) }
   }
 
-  pub const fn to_bytes(self) -> [u8; 5] {
-    let [c0, c1, c2, c3, c4] = self.0;
-    [c0 as u8, c1 as u8, c2 as u8, c3 as u8, c4 as u8]
+  pub const fn to_bytes(self) -> [u8; N] {
+    *self.as_bytes()
   }
 
-  pub const fn as_bytes(&self) -> &[u8; 5] {
-    unsafe { std::mem::transmute(&self.0) }
+  pub const fn as_bytes(&self) -> &[u8; N] {
+    unsafe { &*(&raw const self.0).cast::<[u8; N]>() }
   }
 
   pub const fn as_str(&self) -> &str {
     unsafe { str::from_utf8_unchecked(self.as_bytes()) }
   }
 
+  /// Display the word in lowercase, leaving it stored in uppercase
+  pub const fn display_lower(self) -> DisplayLower<N> {
+    DisplayLower(self)
+  }
+
+  /// How many times each letter appears, indexed by [`Letter::index`]
+  pub const fn letter_counts(&self) -> [u8; 26] {
+    let mut counts = [0; 26];
+    let mut i = 0;
+    while i < N {
+      counts[self.0[i].index()] += 1;
+      i += 1;
+    }
+    counts
+  }
+
   /// Every letter in the word is unique
   pub const fn is_unique(&self) -> bool {
-    let [c0, c1, c2, c3, c4] = self.to_bytes();
-    c1 != c0 &&
-    c2 != c1 && c2 != c0 &&
-    c3 != c2 && c3 != c1 && c3 != c0 &&
-    c4 != c3 && c4 != c2 && c4 != c1 && c4 != c0
+    let counts = self.letter_counts();
+    let mut i = 0;
+    while i < 26 {
+      if counts[i] > 1 {
+        return false;
+      }
+      i += 1;
+    }
+    true
   }
 }
 
-impl std::fmt::Display for Word {
+impl<const N: usize> std::fmt::Display for Word<N> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     self.as_str().fmt(f)
   }
 }
+
+/// See [`Word::display_lower`]
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayLower<const N: usize>(Word<N>);
+
+impl<const N: usize> std::fmt::Display for DisplayLower<N> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let bytes = self.0.to_bytes().map(|b| b.to_ascii_lowercase());
+    // lowercase ASCII letters are still valid UTF-8
+    unsafe { str::from_utf8_unchecked(&bytes) }.fmt(f)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordParseError {
+  /// The word didn't have the expected number of letters
+  WrongLength { expected: usize, found: usize },
+  /// The word contained something other than an ASCII letter
+  InvalidChar(char),
+}
+
+impl std::fmt::Display for WordParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::WrongLength { expected, found } => write!(f, "expected {expected} letters, found {found}"),
+      Self::InvalidChar(ch) => write!(f, "{ch:?} is not an ASCII letter"),
+    }
+  }
+}
+
+impl std::error::Error for WordParseError {}
+
+impl<const N: usize> std::str::FromStr for Word<N> {
+  type Err = WordParseError;
+
+  /// Accepts ASCII letters in any case
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Some(ch) = s.chars().find(|ch| !ch.is_ascii_alphabetic()) {
+      return Err(WordParseError::InvalidChar(ch));
+    }
+    let bytes = <[u8; N]>::try_from(s.as_bytes())
+      .map_err(|_| WordParseError::WrongLength { expected: N, found: s.len() })?;
+    Ok(unsafe { Self::from_bytes_unchecked(bytes.map(|b| b.to_ascii_uppercase())) })
+  }
+}
+
+/// A letter is a single-character string
+#[cfg(feature = "serde")]
+impl serde::Serialize for Letter {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_char(char::from(self.to_u8()))
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Letter {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let ch = char::deserialize(deserializer)?;
+    u8::try_from(ch).ok()
+      .and_then(|b| Letter::from_u8(b.to_ascii_uppercase()))
+      .ok_or_else(|| serde::de::Error::custom(format_args!("{ch:?} is not an ASCII letter")))
+  }
+}
+
+/// A word is its string of letters
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Word<N> {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(self.as_str())
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Word<N> {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    String::deserialize(deserializer)?
+      .parse()
+      .map_err(serde::de::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse() {
+    assert_eq!("crane".parse::<Word>(), Ok(Word::from_bytes(*b"CRANE").unwrap()));
+    assert_eq!("CrAnE".parse::<Word>(), Ok(Word::from_bytes(*b"CRANE").unwrap()));
+    assert_eq!("planet".parse::<Word<6>>(), Ok(Word::from_bytes(*b"PLANET").unwrap()));
+    assert_eq!("cranes".parse::<Word>(), Err(WordParseError::WrongLength { expected: 5, found: 6 }));
+    assert_eq!("".parse::<Word>(), Err(WordParseError::WrongLength { expected: 5, found: 0 }));
+    assert_eq!("cr4ne".parse::<Word>(), Err(WordParseError::InvalidChar('4')));
+    assert_eq!("cafés".parse::<Word>(), Err(WordParseError::InvalidChar('é')));
+    assert_eq!("cr ne".parse::<Word>(), Err(WordParseError::InvalidChar(' ')));
+  }
+
+  #[test]
+  fn test_letter_iter() {
+    let letters: Vec<Letter> = Letter::iter().collect();
+    assert_eq!(letters.len(), 26);
+    for (i, ch) in letters.into_iter().enumerate() {
+      assert_eq!(ch.index(), i);
+    }
+    assert_eq!(Letter::ALL.iter().map(Letter::to_string).collect::<String>(), "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+  }
+
+  #[test]
+  fn test_letter_counts() {
+    let counts = Word::from_bytes(*b"EERIE").unwrap().letter_counts();
+    for (i, &n) in counts.iter().enumerate() {
+      let expected = match b'A' + i as u8 {
+        b'E' => 3,
+        b'R' | b'I' => 1,
+        _ => 0,
+      };
+      assert_eq!(n, expected, "{}", (b'A' + i as u8) as char);
+    }
+    assert!(!Word::from_bytes(*b"EERIE").unwrap().is_unique());
+    assert!(Word::from_bytes(*b"CRANE").unwrap().is_unique());
+  }
+
+  #[test]
+  fn test_display_lower() {
+    let word = Word::from_bytes(*b"CRANE").unwrap();
+    assert_eq!(word.display_lower().to_string(), "crane");
+    assert_eq!(format!("{:>6}", word.display_lower()), " crane");
+    assert_eq!(word.to_string(), "CRANE");
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn test_serde() {
+    let word = Word::from_bytes(*b"CRANE").unwrap();
+    let json = serde_json::to_string(&word).unwrap();
+    assert_eq!(json, "\"CRANE\"");
+    assert_eq!(serde_json::from_str::<Word>(&json).unwrap(), word);
+    assert_eq!(serde_json::from_str::<Word>("\"crane\"").unwrap(), word);
+    assert!(serde_json::from_str::<Word>("\"CRANES\"").is_err());
+    assert!(serde_json::from_str::<Word>("\"CR4NE\"").is_err());
+
+    let json = serde_json::to_string(&Letter::Q).unwrap();
+    assert_eq!(json, "\"Q\"");
+    assert_eq!(serde_json::from_str::<Letter>(&json).unwrap(), Letter::Q);
+    assert!(serde_json::from_str::<Letter>("\"?\"").is_err());
+  }
+}
@@ -0,0 +1,56 @@
+use wordle_helper::{check_word, dictionary::ANSWERS, Guesser, Word};
+
+#[test]
+fn solves_with_public_api() {
+  let mut guesser = Guesser::new(Vec::new());
+  for answer in ANSWERS.iter().step_by(ANSWERS.len() / 8) {
+    guesser.reset();
+    let mut won = false;
+    for turn in 1..=6 {
+      let guess: Word = *guesser.guess().expect("should always have a suggestion");
+      let feedback = check_word(*answer, guess);
+      if guesser.analyze(std::array::from_fn(|i| (guess[i], feedback[i]))).unwrap().solved {
+        won = true;
+        break;
+      }
+      guesser.prune(turn);
+    }
+    assert!(won, "failed to solve {answer}");
+  }
+}
+
+/// Words that have tripped up the solver, mostly for repeating letters; add any new ones here
+const HARD_WORDS: &[&str] = &[
+  "MUMMY", "EERIE", "FLUFF", "MAMMA", "NANNY", "JAZZY", "FUZZY", "PUPPY", "GEESE", "LEVEL",
+  "KAYAK", "SASSY", "VIVID", "ERROR", "EMCEE", "ABBEY", "SKILL", "BOBBY", "TATTY", "QUEUE",
+  "XYLYL", "FATES", "ESSES",
+];
+
+#[test]
+fn solves_hard_words() {
+  let mut guesser = Guesser::new(Vec::new());
+  let mut slow = Vec::new();
+  for answer in HARD_WORDS {
+    let answer: Word = answer.parse().unwrap();
+    assert!(ANSWERS.contains(&answer), "{answer} isn't in the answer list");
+    guesser.reset();
+    // past six turns the game would be lost, but the answer should still never be ruled out
+    let turns = (1..=20).find(|&turn| {
+      let guess: Word = *guesser.guess().unwrap_or_else(|| panic!("ran out of suggestions solving {answer}"));
+      if guess == answer {
+        return true;
+      }
+      let feedback = check_word(answer, guess);
+      guesser.analyze(std::array::from_fn(|i| (guess[i], feedback[i])))
+        .unwrap_or_else(|e| panic!("feedback for {guess} was called contradictory solving {answer}: {e}"));
+      guesser.prune(turn);
+      assert!(guesser.candidates().contains(&answer), "{guess} ruled out {answer}");
+      false
+    });
+    let turns = turns.unwrap_or_else(|| panic!("failed to solve {answer} in 20 turns"));
+    if turns > 6 {
+      slow.push(format!("{answer} in {turns}"));
+    }
+  }
+  println!("solved past six turns: {slow:?}");
+}
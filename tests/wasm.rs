@@ -0,0 +1,24 @@
+//! Smoke test for the `wasm` feature; also build the library with
+//! `cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm`
+#![cfg(feature = "wasm")]
+
+use std::num::NonZeroUsize;
+use wordle_helper::{check_word, dictionary::ANSWERS, Guesser};
+
+#[test]
+fn solves_without_threads() {
+  let mut guesser = Guesser::new(Vec::new());
+  // would spawn threads without the feature
+  guesser.set_threads(NonZeroUsize::new(8).unwrap());
+  let answer = ANSWERS[ANSWERS.len() / 2];
+  for turn in 1..=6 {
+    let guess = *guesser.guess().unwrap();
+    if guess == answer {
+      return;
+    }
+    let fb = check_word(answer, guess);
+    guesser.analyze(std::array::from_fn(|i| (guess[i], fb[i]))).unwrap();
+    guesser.prune(turn);
+  }
+  panic!("failed to solve {answer}");
+}